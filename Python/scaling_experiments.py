@@ -51,12 +51,15 @@ def strong_scaling_experiment(result_file_path):
             'MeanPar': round(mean_par, 4),
             'StdPar': round(std_par, 4),
             'Speedup': round(speedup, 2),
-            'Efficiency': round(efficiency, 2)
+            'Efficiency': round(efficiency, 2),
+            'NPoints': n_samples,
+            'K': n_clusters,
+            'Dim': n_features
         })
         print(f"ITERATION {cores}")
 
     with open(result_file_path, 'w', newline='') as f:
-        writer = csv.DictWriter(f, fieldnames=['Threads','MeanSeq','StdSeq','MeanPar','StdPar','Speedup','Efficiency'])
+        writer = csv.DictWriter(f, fieldnames=['Threads','MeanSeq','StdSeq','MeanPar','StdPar','Speedup','Efficiency','NPoints','K','Dim'])
         writer.writeheader()
         for r in results:
             writer.writerow(r)
@@ -88,12 +91,15 @@ def weak_scaling_experiment(result_file_path):
             'MeanPar': round(mean_par, 4),
             'StdPar': round(std_par, 4),
             'Speedup': round(speedup, 2),
-            'Efficiency': round(efficiency, 2)
+            'Efficiency': round(efficiency, 2),
+            'NPoints': n_samples,
+            'K': n_clusters,
+            'Dim': n_features
         })
         print(f"ITERATION {cores}")
 
     with open(result_file_path, 'w', newline='') as f:
-        writer = csv.DictWriter(f, fieldnames=['Threads','MeanSeq','StdSeq','MeanPar','StdPar','Speedup','Efficiency'])
+        writer = csv.DictWriter(f, fieldnames=['Threads','MeanSeq','StdSeq','MeanPar','StdPar','Speedup','Efficiency','NPoints','K','Dim'])
         writer.writeheader()
         for r in results:
             writer.writerow(r)
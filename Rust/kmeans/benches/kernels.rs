@@ -0,0 +1,127 @@
+//! Micro-benchmarks of the k-means building blocks over a grid of point
+//! counts and cluster counts, plus one Lloyd iteration over a range of
+//! dimensions with the N-dimensional kernels. Run with
+//! `cargo bench --bench kernels`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use kmeans::generators::{make_blobs, make_blobs_nd};
+use kmeans::kmeans_nd::{kmeans_nd_par, kmeans_nd_seq};
+use kmeans::kmeans_par::{accumulate_par, assign_par, kmeans_par_run};
+use kmeans::kmeans_seq::{accumulate, assign, kmeans_seq_run};
+use kmeans::point::{euclidean_distance, Point};
+
+const NS: [usize; 3] = [10_000, 100_000, 1_000_000];
+const KS: [usize; 3] = [4, 16, 64];
+const DIMS: [usize; 4] = [2, 8, 32, 128];
+// Point and cluster counts of the dimension sweep.
+const DIM_N: usize = 100_000;
+const DIM_K: usize = 16;
+
+// Blobs data shared by every k of one n.
+fn dataset(n: usize) -> Vec<Point> {
+    make_blobs(n, 8, 1.5, (-10.0, 10.0), 42).points
+}
+
+// The first `k` points as centroids, and the matching assignments.
+fn setup(points: &[Point], k: usize) -> (Vec<Point>, Vec<usize>) {
+    let centroids = points[..k].to_vec();
+    let mut assignments = vec![0; points.len()];
+    assign(points, &centroids, &mut assignments);
+    (centroids, assignments)
+}
+
+fn bench_distance(c: &mut Criterion) {
+    let a = Point { x: 1.5, y: -2.0 };
+    let b = Point { x: -0.5, y: 3.25 };
+    c.bench_function("euclidean_distance", |bench| {
+        bench.iter(|| euclidean_distance(black_box(&a), black_box(&b)))
+    });
+}
+
+fn bench_assignment(c: &mut Criterion) {
+    let mut group = c.benchmark_group("assignment");
+    for n in NS {
+        let points = dataset(n);
+        for k in KS {
+            let (centroids, mut assignments) = setup(&points, k);
+            let id = format!("n={n}/k={k}");
+            group.throughput(Throughput::Elements(n as u64));
+            group.bench_function(BenchmarkId::new("seq", &id), |bench| {
+                bench.iter(|| assign(&points, &centroids, &mut assignments))
+            });
+            group.bench_function(BenchmarkId::new("par", &id), |bench| {
+                bench.iter(|| assign_par(&points, &centroids, &mut assignments))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_accumulation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("accumulation");
+    for n in NS {
+        let points = dataset(n);
+        for k in KS {
+            let (_, assignments) = setup(&points, k);
+            let id = format!("n={n}/k={k}");
+            group.throughput(Throughput::Elements(n as u64));
+            group.bench_function(BenchmarkId::new("seq", &id), |bench| {
+                bench.iter(|| accumulate(&points, &assignments, k))
+            });
+            group.bench_function(BenchmarkId::new("par_fold_reduce", &id), |bench| {
+                bench.iter(|| accumulate_par(&points, &assignments, k))
+            });
+        }
+    }
+    group.finish();
+}
+
+// One Lloyd iteration of `kmeans_seq_run` and `kmeans_par_run` from fixed
+// initial centroids, including their per-run allocations.
+fn bench_iteration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iteration");
+    for n in NS {
+        let points = dataset(n);
+        for k in KS {
+            let initial = points[..k].to_vec();
+            let id = format!("n={n}/k={k}");
+            group.throughput(Throughput::Elements(n as u64));
+            group.bench_function(BenchmarkId::new("seq", &id), |bench| {
+                bench.iter(|| kmeans_seq_run(&points, k, 1, 0.0, Some(initial.clone())))
+            });
+            group.bench_function(BenchmarkId::new("par", &id), |bench| {
+                bench.iter(|| kmeans_par_run(&points, k, 1, 0.0, Some(initial.clone())))
+            });
+        }
+    }
+    group.finish();
+}
+
+// One iteration of the N-dimensional kernels at a fixed point and cluster
+// count, so only the dimension varies.
+fn bench_dimension(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dimension");
+    for dim in DIMS {
+        let data = make_blobs_nd(DIM_N, dim, 8, 1.5, (-10.0, 10.0), 42);
+        let initial = data[..DIM_K * dim].to_vec();
+        let id = format!("n={DIM_N}/k={DIM_K}/dim={dim}");
+        group.throughput(Throughput::Elements(DIM_N as u64));
+        group.bench_function(BenchmarkId::new("seq", &id), |bench| {
+            bench.iter(|| kmeans_nd_seq(&data, dim, 1, 0.0, initial.clone()))
+        });
+        group.bench_function(BenchmarkId::new("par", &id), |bench| {
+            bench.iter(|| kmeans_nd_par(&data, dim, 1, 0.0, initial.clone()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_distance,
+    bench_assignment,
+    bench_accumulation,
+    bench_iteration,
+    bench_dimension
+);
+criterion_main!(benches);
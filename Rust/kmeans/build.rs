@@ -0,0 +1,60 @@
+// Records build metadata for `environment::Environment` as compile-time
+// environment variables.
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let out = Command::new(program).args(args).output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(out.stdout).ok()?;
+    Some(text.trim().to_string())
+}
+
+// Version of `package` in the lock file, if there is one.
+fn locked_version(lock: &Path, package: &str) -> Option<String> {
+    let text = fs::read_to_string(lock).ok()?;
+    let needle = format!("name = \"{}\"", package);
+    let mut lines = text.lines();
+    lines.find(|line| line.trim() == needle)?;
+    let version = lines.next()?.trim().strip_prefix("version = ")?;
+    Some(version.trim_matches('"').to_string())
+}
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+
+    let rustc_version = output(&rustc, &["--version"]).unwrap_or_default();
+    let profile = env::var("PROFILE").unwrap_or_default();
+    let commit = output("git", &["rev-parse", "HEAD"]).unwrap_or_default();
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(str::to_lowercase))
+        .collect();
+    features.sort();
+    let lock = Path::new(&manifest_dir).join("Cargo.lock");
+    let rayon = locked_version(&lock, "rayon").unwrap_or_default();
+
+    println!("cargo:rustc-env=KMEANS_RUSTC_VERSION={}", rustc_version);
+    println!("cargo:rustc-env=KMEANS_PROFILE={}", profile);
+    println!("cargo:rustc-env=KMEANS_GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=KMEANS_FEATURES={}", features.join(","));
+    println!("cargo:rustc-env=KMEANS_RAYON_VERSION={}", rayon);
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    // Rebuild when HEAD moves, whether it is detached or on a branch.
+    if let Some(git_dir) = output("git", &["rev-parse", "--absolute-git-dir"]) {
+        let git_dir = Path::new(&git_dir);
+        println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+        if let Some(head_ref) = output("git", &["symbolic-ref", "-q", "HEAD"]) {
+            println!(
+                "cargo:rerun-if-changed={}",
+                git_dir.join(head_ref).display()
+            );
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::error::{Error, Result};
+use crate::point::Point;
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Header {
+    /// Treat the first row as a header when its selected columns are not numeric.
+    Auto,
+    Present,
+    Absent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MalformedRows {
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub header: Header,
+    pub x: Column,
+    pub y: Column,
+    pub malformed: MalformedRows,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            header: Header::Auto,
+            x: Column::Index(0),
+            y: Column::Index(1),
+            malformed: MalformedRows::Fail,
+        }
+    }
+}
+
+/// Rows read from a CSV file. `records[i]` is the raw input row that produced
+/// `points[i]`; rows skipped as malformed are listed by line number.
+#[derive(Debug, Clone)]
+pub struct CsvDataset {
+    pub headers: Option<StringRecord>,
+    pub records: Vec<StringRecord>,
+    pub points: Vec<Point>,
+    pub skipped_lines: Vec<u64>,
+}
+
+fn line_of(record: &StringRecord) -> u64 {
+    record.position().map_or(0, |p| p.line())
+}
+
+fn resolve(column: &Column, headers: Option<&StringRecord>) -> Result<usize> {
+    match column {
+        Column::Index(i) => Ok(*i),
+        Column::Name(name) => headers
+            .and_then(|h| h.iter().position(|field| field.trim() == name))
+            .ok_or_else(|| Error::InvalidFormat(format!("no column named '{}'", name))),
+    }
+}
+
+fn parse_field(record: &StringRecord, index: usize) -> std::result::Result<f64, String> {
+    let field = record
+        .get(index)
+        .ok_or_else(|| format!("missing column {}", index))?;
+    let value = field
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("cannot parse '{}' in column {} as a number", field, index))?;
+    if !value.is_finite() {
+        return Err(format!("non-finite value '{}' in column {}", field, index));
+    }
+    Ok(value)
+}
+
+fn parse_point(record: &StringRecord, x: usize, y: usize) -> std::result::Result<Point, String> {
+    Ok(Point {
+        x: parse_field(record, x)?,
+        y: parse_field(record, y)?,
+    })
+}
+
+/// Streams the rows of a CSV file, calling `visit` with each raw record and
+/// the point parsed from it. Returns the header row (if any) and the line
+/// numbers of rows skipped as malformed.
+pub(crate) fn visit_csv(
+    path: impl AsRef<Path>,
+    options: &CsvOptions,
+    mut visit: impl FnMut(StringRecord, Point) -> Result<()>,
+) -> Result<(Option<StringRecord>, Vec<u64>)> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)?;
+    let mut rows = reader.records();
+
+    let first = match rows.next() {
+        Some(record) => Some(record?),
+        None => None,
+    };
+    let has_header = match options.header {
+        Header::Present => true,
+        Header::Absent => false,
+        Header::Auto => match &first {
+            Some(record) => {
+                let x = resolve(&options.x, Some(record)).unwrap_or(0);
+                let y = resolve(&options.y, Some(record)).unwrap_or(1);
+                parse_point(record, x, y).is_err()
+            }
+            None => false,
+        },
+    };
+    let (headers, first_row) = if has_header {
+        (first, None)
+    } else {
+        (None, first)
+    };
+    let x = resolve(&options.x, headers.as_ref())?;
+    let y = resolve(&options.y, headers.as_ref())?;
+
+    let mut skipped_lines = Vec::new();
+    for record in first_row.into_iter().map(Ok).chain(rows) {
+        let record = record?;
+        match parse_point(&record, x, y) {
+            Ok(point) => visit(record, point)?,
+            Err(message) => match options.malformed {
+                MalformedRows::Skip => skipped_lines.push(line_of(&record)),
+                MalformedRows::Fail => {
+                    return Err(Error::Parse {
+                        line: line_of(&record),
+                        message,
+                    })
+                }
+            },
+        }
+    }
+    Ok((headers, skipped_lines))
+}
+
+pub fn read_csv(path: impl AsRef<Path>, options: &CsvOptions) -> Result<CsvDataset> {
+    let mut records = Vec::new();
+    let mut points = Vec::new();
+    let (headers, skipped_lines) = visit_csv(path, options, |record, point| {
+        records.push(record);
+        points.push(point);
+        Ok(())
+    })?;
+    Ok(CsvDataset {
+        headers,
+        records,
+        points,
+        skipped_lines,
+    })
+}
+
+/// Writes every input row of `dataset` followed by its assigned cluster.
+pub fn write_labeled_csv(
+    path: impl AsRef<Path>,
+    dataset: &CsvDataset,
+    labels: &[usize],
+    delimiter: u8,
+) -> Result<()> {
+    if labels.len() != dataset.records.len() {
+        return Err(Error::InvalidFormat(format!(
+            "{} labels for {} rows",
+            labels.len(),
+            dataset.records.len()
+        )));
+    }
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_path(path)?;
+    if let Some(headers) = &dataset.headers {
+        let mut row = headers.clone();
+        row.push_field("cluster");
+        writer.write_record(&row)?;
+    }
+    for (record, label) in dataset.records.iter().zip(labels.iter()) {
+        let mut row = record.clone();
+        row.push_field(&label.to_string());
+        writer.write_record(&row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_non_finite_values() {
+        let path = std::env::temp_dir().join(format!("kmeans-{}-nan.csv", std::process::id()));
+        std::fs::write(&path, "x,y\n1,2\nnan,3\n4,inf\n5,6\n").unwrap();
+        let failed = read_csv(&path, &CsvOptions::default());
+        let skipped = read_csv(
+            &path,
+            &CsvOptions {
+                malformed: MalformedRows::Skip,
+                ..CsvOptions::default()
+            },
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(failed, Err(Error::Parse { line: 3, .. })));
+        let skipped = skipped.unwrap();
+        assert_eq!(skipped.points.len(), 2);
+        assert_eq!(skipped.skipped_lines, vec![3, 4]);
+    }
+}
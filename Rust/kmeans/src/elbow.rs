@@ -0,0 +1,168 @@
+use clap::Parser;
+use kmeans::environment::Environment;
+use kmeans::error::{Error, Result};
+use kmeans::generators::make_blobs;
+use kmeans::sweep::{find_elbow, k_sweep, SweepResult};
+use plotters::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Sweep k over synthetic blobs and locate the elbow of the inertia curve.
+#[derive(Parser)]
+#[command(name = "elbow")]
+struct Cli {
+    #[arg(long, default_value_t = 5000)]
+    n_points: usize,
+    /// Blobs in the generated data
+    #[arg(long, default_value_t = 4)]
+    true_k: usize,
+    #[arg(long, default_value_t = 2)]
+    k_min: usize,
+    #[arg(long, default_value_t = 30)]
+    k_max: usize,
+    #[arg(long, default_value_t = 100)]
+    max_iters: usize,
+    #[arg(long, default_value_t = 1e-3)]
+    tolerance: f64,
+    /// Seeded restarts per k; the lowest inertia is kept
+    #[arg(long, default_value_t = 10)]
+    n_init: usize,
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+    /// Sweep table; the environment is written beside it as `<name>.environment.json`
+    #[arg(long, default_value = "elbow_sweep.csv")]
+    csv: PathBuf,
+    /// Inertia and silhouette plot
+    #[arg(long, default_value = "elbow_sweep.png")]
+    plot: PathBuf,
+}
+
+fn save_csv(filename: &Path, results: &[SweepResult]) -> Result<()> {
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "K,Inertia,Silhouette,Time")?;
+    for r in results {
+        writeln!(
+            file,
+            "{},{:.4},{:.4},{:.4}",
+            r.k, r.inertia, r.silhouette, r.time
+        )?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+fn plot_elbow(filename: &Path, results: &[SweepResult], elbow: Option<usize>) -> Result<()> {
+    let root = BitMapBackend::new(filename, (800, 900)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let (upper, lower) = root.split_vertically(550);
+    let k_min = results.first().map_or(0, |r| r.k);
+    let k_max = results.last().map_or(0, |r| r.k);
+    let max_inertia = results.iter().map(|r| r.inertia).fold(0.0, f64::max);
+
+    let mut chart = ChartBuilder::on(&upper)
+        .caption("Elbow method", ("sans-serif", 30))
+        .margin(40)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d(k_min..k_max, 0.0..max_inertia * 1.05)?;
+    chart
+        .configure_mesh()
+        .x_desc("k")
+        .y_desc("Inertia")
+        .draw()?;
+    chart
+        .draw_series(LineSeries::new(
+            results.iter().map(|r| (r.k, r.inertia)),
+            &BLUE,
+        ))?
+        .label("Inertia")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 1, y)], BLUE));
+    if let Some(r) = elbow.and_then(|k| results.iter().find(|r| r.k == k)) {
+        chart
+            .draw_series(std::iter::once(Circle::new(
+                (r.k, r.inertia),
+                6,
+                RED.filled(),
+            )))?
+            .label(format!("Elbow k={}", r.k))
+            .legend(|(x, y)| Circle::new((x, y), 4, RED.filled()));
+    }
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    let mut chart = ChartBuilder::on(&lower)
+        .margin(40)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d(k_min..k_max, -1.0..1.0)?;
+    chart
+        .configure_mesh()
+        .x_desc("k")
+        .y_desc("Silhouette")
+        .draw()?;
+    chart.draw_series(LineSeries::new(
+        results.iter().map(|r| (r.k, r.silhouette)),
+        &GREEN,
+    ))?;
+    root.present()?;
+    Ok(())
+}
+
+fn run(cli: Cli) -> Result<()> {
+    if cli.k_min == 0 || cli.k_min > cli.k_max || cli.k_max > cli.n_points {
+        return Err(Error::InvalidFormat(format!(
+            "need 1 <= k-min <= k-max <= n-points, got {}..={} for {} points",
+            cli.k_min, cli.k_max, cli.n_points
+        )));
+    }
+    if cli.true_k == 0 || cli.n_init == 0 {
+        return Err(Error::InvalidFormat(
+            "true-k and n-init must be at least 1".to_string(),
+        ));
+    }
+    let points = make_blobs(cli.n_points, cli.true_k, 1.5, (-10.0, 10.0), cli.seed).points;
+    println!("Running k sweep for k = {}..={}...", cli.k_min, cli.k_max);
+    let results = k_sweep(
+        &points,
+        cli.k_min..=cli.k_max,
+        cli.max_iters,
+        cli.tolerance,
+        cli.n_init,
+        cli.seed,
+    );
+    for r in &results {
+        println!(
+            "k = {:>2}: inertia = {:.2}, silhouette = {:.4}, time = {:.4}s",
+            r.k, r.inertia, r.silhouette, r.time
+        );
+    }
+    let elbow = find_elbow(&results);
+    match elbow {
+        Some(k) => println!("Elbow detected at k = {}", k),
+        None => println!("No elbow detected"),
+    }
+    save_csv(&cli.csv, &results)?;
+    Environment::capture().save(Environment::sidecar_path(&cli.csv))?;
+    plot_elbow(&cli.plot, &results, elbow)?;
+    println!(
+        "Sweep written to {} and {}",
+        cli.csv.display(),
+        cli.plot.display()
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The machine and build an experiment ran on, so results from different
+/// laptops and servers can be told apart. Fields that can't be determined
+/// on this platform are `None` or empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    pub cpu_model: Option<String>,
+    pub logical_cores: usize,
+    pub physical_cores: Option<usize>,
+    pub memory_bytes: Option<u64>,
+    /// Distribution name where known, otherwise the OS family.
+    pub os: String,
+    pub kernel: Option<String>,
+    pub arch: String,
+    pub rustc: String,
+    /// Cargo profile the crate was built with ("release" or "debug").
+    pub profile: String,
+    pub git_commit: Option<String>,
+    pub features: Vec<String>,
+    pub crate_version: String,
+    pub rayon_version: String,
+    /// Threads in rayon's global pool.
+    pub rayon_threads: usize,
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+// Value of the first `key : value` line of /proc/cpuinfo with `key`.
+fn cpuinfo_field<'a>(cpuinfo: &'a str, key: &str) -> Option<&'a str> {
+    cpuinfo.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k.trim() == key).then(|| v.trim())
+    })
+}
+
+// Distinct (physical id, core id) pairs; absent on some architectures.
+fn physical_cores(cpuinfo: &str) -> Option<usize> {
+    let mut cores = BTreeSet::new();
+    let mut package = None;
+    for line in cpuinfo.lines() {
+        let Some((k, v)) = line.split_once(':') else {
+            continue;
+        };
+        match k.trim() {
+            "physical id" => package = Some(v.trim().to_string()),
+            "core id" => {
+                cores.insert((package.clone(), v.trim().to_string()));
+            }
+            _ => {}
+        }
+    }
+    (!cores.is_empty()).then_some(cores.len())
+}
+
+fn memory_bytes() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let kb = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+fn os_name() -> String {
+    fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|text| {
+            text.lines()
+                .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+                .map(|name| name.trim_matches('"').to_string())
+        })
+        .unwrap_or_else(|| std::env::consts::OS.to_string())
+}
+
+impl Environment {
+    /// Inspects the running machine; build details come from `build.rs`.
+    pub fn capture() -> Self {
+        let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let cpu_model = cpuinfo_field(&cpuinfo, "model name")
+            .or_else(|| cpuinfo_field(&cpuinfo, "Model"))
+            .map(str::to_string);
+        Environment {
+            cpu_model,
+            logical_cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            physical_cores: physical_cores(&cpuinfo),
+            memory_bytes: memory_bytes(),
+            os: os_name(),
+            kernel: fs::read_to_string("/proc/sys/kernel/osrelease")
+                .ok()
+                .map(|s| s.trim().to_string()),
+            arch: std::env::consts::ARCH.to_string(),
+            rustc: env!("KMEANS_RUSTC_VERSION").to_string(),
+            profile: env!("KMEANS_PROFILE").to_string(),
+            git_commit: non_empty(env!("KMEANS_GIT_COMMIT")),
+            features: env!("KMEANS_FEATURES")
+                .split(',')
+                .filter(|f| !f.is_empty())
+                .map(str::to_string)
+                .collect(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            rayon_version: env!("KMEANS_RAYON_VERSION").to_string(),
+            rayon_threads: rayon::current_num_threads(),
+        }
+    }
+
+    /// Writes the environment as pretty-printed JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Where the environment of a result file written outside an experiment
+    /// output directory is recorded: `results.csv` gets
+    /// `results.environment.json` beside it.
+    pub fn sidecar_path(results: impl AsRef<Path>) -> PathBuf {
+        results.as_ref().with_extension("environment.json")
+    }
+
+    /// `key: value` lines, for terminal output.
+    pub fn lines(&self) -> Vec<String> {
+        let unknown = || "unknown".to_string();
+        vec![
+            format!(
+                "cpu_model: {}",
+                self.cpu_model.clone().unwrap_or_else(unknown)
+            ),
+            format!("logical_cores: {}", self.logical_cores),
+            format!(
+                "physical_cores: {}",
+                self.physical_cores.map_or_else(unknown, |n| n.to_string())
+            ),
+            format!(
+                "memory_bytes: {}",
+                self.memory_bytes.map_or_else(unknown, |n| n.to_string())
+            ),
+            format!("os: {}", self.os),
+            format!("kernel: {}", self.kernel.clone().unwrap_or_else(unknown)),
+            format!("arch: {}", self.arch),
+            format!("rustc: {}", self.rustc),
+            format!("profile: {}", self.profile),
+            format!(
+                "git_commit: {}",
+                self.git_commit.clone().unwrap_or_else(unknown)
+            ),
+            format!("features: {}", self.features.join(",")),
+            format!("crate_version: {}", self.crate_version),
+            format!("rayon_version: {}", self.rayon_version),
+            format!("rayon_threads: {}", self.rayon_threads),
+        ]
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
+    Binary(bincode::Error),
+    Csv(csv::Error),
+    Zip(zip::result::ZipError),
+    #[cfg(feature = "parquet")]
+    Arrow(arrow::error::ArrowError),
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::errors::ParquetError),
+    Parse {
+        line: u64,
+        message: String,
+    },
+    Plot(String),
+    ThreadPool(rayon::ThreadPoolBuildError),
+    IncompatibleVersion {
+        found: u32,
+        supported: u32,
+    },
+    InvalidFormat(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Json(e) => write!(f, "JSON error: {}", e),
+            Error::Toml(e) => write!(f, "TOML error: {}", e),
+            Error::Binary(e) => write!(f, "binary encoding error: {}", e),
+            Error::Csv(e) => write!(f, "CSV error: {}", e),
+            Error::Zip(e) => write!(f, "zip archive error: {}", e),
+            #[cfg(feature = "parquet")]
+            Error::Arrow(e) => write!(f, "Arrow error: {}", e),
+            #[cfg(feature = "parquet")]
+            Error::Parquet(e) => write!(f, "Parquet error: {}", e),
+            Error::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            Error::Plot(msg) => write!(f, "plotting error: {}", msg),
+            Error::ThreadPool(e) => write!(f, "cannot build thread pool: {}", e),
+            Error::IncompatibleVersion { found, supported } => write!(
+                f,
+                "incompatible model format version {} (this build reads version {})",
+                found, supported
+            ),
+            Error::InvalidFormat(msg) => write!(f, "invalid format: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+            Error::Toml(e) => Some(e),
+            Error::Binary(e) => Some(e),
+            Error::Csv(e) => Some(e),
+            Error::ThreadPool(e) => Some(e),
+            Error::Zip(e) => Some(e),
+            #[cfg(feature = "parquet")]
+            Error::Arrow(e) => Some(e),
+            #[cfg(feature = "parquet")]
+            Error::Parquet(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Self {
+        Error::Toml(e)
+    }
+}
+
+impl From<bincode::Error> for Error {
+    fn from(e: bincode::Error) -> Self {
+        Error::Binary(e)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        Error::Csv(e)
+    }
+}
+
+impl From<rayon::ThreadPoolBuildError> for Error {
+    fn from(e: rayon::ThreadPoolBuildError) -> Self {
+        Error::ThreadPool(e)
+    }
+}
+
+impl<E: std::error::Error + Send + Sync> From<plotters::drawing::DrawingAreaErrorKind<E>>
+    for Error
+{
+    fn from(e: plotters::drawing::DrawingAreaErrorKind<E>) -> Self {
+        Error::Plot(e.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for Error {
+    fn from(e: zip::result::ZipError) -> Self {
+        Error::Zip(e)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<arrow::error::ArrowError> for Error {
+    fn from(e: arrow::error::ArrowError) -> Self {
+        Error::Arrow(e)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for Error {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        Error::Parquet(e)
+    }
+}
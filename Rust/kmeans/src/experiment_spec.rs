@@ -0,0 +1,227 @@
+use crate::environment::Environment;
+use crate::error::{Error, Result};
+use crate::generators::DatasetSpec;
+use crate::scaling::BenchConfig;
+use crate::scaling_plots::PlotOptions;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrongSpec {
+    pub n_points: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeakSpec {
+    /// Points per thread; the run with `t` threads uses `t * base_points`.
+    pub base_points: usize,
+}
+
+/// Sweep over the number of clusters at a fixed thread count.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KScalingSpec {
+    pub n_points: usize,
+    pub ks: Vec<usize>,
+    pub threads: usize,
+}
+
+/// Sweep over the coordinates per point at a fixed thread count, using the
+/// spec's `k`. Needs a uniform or blobs dataset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DimScalingSpec {
+    pub n_points: usize,
+    pub dims: Vec<usize>,
+    pub threads: usize,
+}
+
+/// Everything a scaling run needs, read from a TOML or JSON file. Omitted
+/// fields take the values the experiments used to hard-code, except that an
+/// experiment whose table is omitted is not run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExperimentSpec {
+    pub dataset: DatasetSpec,
+    pub k: usize,
+    pub max_iters: usize,
+    pub tolerance: f64,
+    pub threads: Vec<usize>,
+    /// Warm-up, repetitions and the other measurement settings, given as
+    /// top-level keys.
+    #[serde(flatten)]
+    pub bench: BenchConfig,
+    pub output_dir: PathBuf,
+    /// Parallel fraction used for the Amdahl and Gustafson curves.
+    pub parallel_fraction: f64,
+    /// Error bars and axis scale of the speedup plots.
+    pub plot: PlotOptions,
+    pub strong: Option<StrongSpec>,
+    pub weak: Option<WeakSpec>,
+    pub k_scaling: Option<KScalingSpec>,
+    pub dim_scaling: Option<DimScalingSpec>,
+}
+
+impl Default for ExperimentSpec {
+    fn default() -> Self {
+        ExperimentSpec {
+            dataset: DatasetSpec::default(),
+            k: 4,
+            max_iters: 100,
+            tolerance: 1e-3,
+            threads: (1..=8).collect(),
+            bench: BenchConfig::default(),
+            output_dir: PathBuf::from("experiments_files"),
+            parallel_fraction: 0.9,
+            plot: PlotOptions::default(),
+            strong: None,
+            weak: None,
+            k_scaling: None,
+            dim_scaling: None,
+        }
+    }
+}
+
+impl ExperimentSpec {
+    /// The strong and weak scaling runs the experiments used to hard-code.
+    pub fn standard() -> Self {
+        ExperimentSpec {
+            strong: Some(StrongSpec { n_points: 100000 }),
+            weak: Some(WeakSpec {
+                base_points: 200000,
+            }),
+            ..ExperimentSpec::default()
+        }
+    }
+
+    /// Parses a `.toml` or `.json` spec.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let spec: ExperimentSpec = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(&text)?,
+            Some("toml") => toml::from_str(&text)?,
+            _ => {
+                return Err(Error::InvalidFormat(format!(
+                    "{} is neither .toml nor .json",
+                    path.display()
+                )))
+            }
+        };
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.k == 0 || self.bench.repetitions == 0 {
+            return Err(Error::InvalidFormat(
+                "k and repetitions must be positive".to_string(),
+            ));
+        }
+        if self.threads.is_empty() || self.threads.contains(&0) {
+            return Err(Error::InvalidFormat(
+                "threads must list at least one positive thread count".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.parallel_fraction) {
+            return Err(Error::InvalidFormat(
+                "parallel_fraction must be between 0 and 1".to_string(),
+            ));
+        }
+        let (valid, requirement) = match self.dataset {
+            DatasetSpec::Uniform { low, high } => (low < high, "uniform dataset needs low < high"),
+            DatasetSpec::Blobs {
+                centers,
+                std,
+                center_box: (low, high),
+            } => (
+                centers >= 1 && std >= 0.0 && low < high,
+                "blobs dataset needs centers >= 1, std >= 0 and center_box low < high",
+            ),
+            DatasetSpec::Moons { noise } => (noise >= 0.0, "moons dataset needs noise >= 0"),
+            DatasetSpec::Circles { factor, noise } => (
+                (0.0..1.0).contains(&factor) && noise >= 0.0,
+                "circles dataset needs 0 <= factor < 1 and noise >= 0",
+            ),
+        };
+        if !valid {
+            return Err(Error::InvalidFormat(requirement.to_string()));
+        }
+        if self.strong.is_none()
+            && self.weak.is_none()
+            && self.k_scaling.is_none()
+            && self.dim_scaling.is_none()
+        {
+            return Err(Error::InvalidFormat(
+                "spec runs no experiment: add [strong], [weak], [k_scaling] or [dim_scaling]"
+                    .to_string(),
+            ));
+        }
+        if self.strong.as_ref().is_some_and(|s| self.k > s.n_points) {
+            return Err(Error::InvalidFormat(
+                "k is larger than strong.n_points".to_string(),
+            ));
+        }
+        if self.weak.as_ref().is_some_and(|w| self.k > w.base_points) {
+            return Err(Error::InvalidFormat(
+                "k is larger than weak.base_points".to_string(),
+            ));
+        }
+        if let Some(sweep) = &self.k_scaling {
+            if sweep.threads == 0 || sweep.ks.is_empty() || sweep.ks.contains(&0) {
+                return Err(Error::InvalidFormat(
+                    "k_scaling needs positive threads and at least one positive k".to_string(),
+                ));
+            }
+            if sweep.ks.iter().any(|&k| k > sweep.n_points) {
+                return Err(Error::InvalidFormat(
+                    "k_scaling has a k larger than n_points".to_string(),
+                ));
+            }
+        }
+        if let Some(sweep) = &self.dim_scaling {
+            if sweep.threads == 0 || sweep.dims.is_empty() || sweep.dims.contains(&0) {
+                return Err(Error::InvalidFormat(
+                    "dim_scaling needs positive threads and at least one positive dimension"
+                        .to_string(),
+                ));
+            }
+            if self.k > sweep.n_points {
+                return Err(Error::InvalidFormat(
+                    "k is larger than dim_scaling.n_points".to_string(),
+                ));
+            }
+            if !self.dataset.has_nd() {
+                return Err(Error::InvalidFormat(
+                    "dim_scaling needs a uniform or blobs dataset".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the output directory and records the spec in it: `source` is
+    /// copied byte for byte, otherwise the spec is written as `spec.toml`.
+    /// The machine and build are recorded in `environment.json`.
+    pub fn prepare_output(&self, source: Option<&Path>) -> Result<()> {
+        fs::create_dir_all(&self.output_dir)?;
+        match source {
+            Some(path) => {
+                let name = path.file_name().ok_or_else(|| {
+                    Error::InvalidFormat("spec path has no file name".to_string())
+                })?;
+                fs::copy(path, self.output_dir.join(name))?;
+            }
+            None => {
+                let text = toml::to_string_pretty(self)
+                    .map_err(|e| Error::InvalidFormat(e.to_string()))?;
+                fs::write(self.output_dir.join("spec.toml"), text)?;
+            }
+        }
+        Environment::capture().save(self.output_dir.join("environment.json"))?;
+        Ok(())
+    }
+
+    pub fn output_path(&self, name: &str) -> PathBuf {
+        self.output_dir.join(name)
+    }
+}
@@ -0,0 +1,197 @@
+/// Contingency table of two labelings: `table[i][j]` counts the points with
+/// true label `i` and predicted label `j`.
+pub fn contingency_table(labels_true: &[usize], labels_pred: &[usize]) -> Vec<Vec<usize>> {
+    assert_eq!(
+        labels_true.len(),
+        labels_pred.len(),
+        "label vectors must have the same length"
+    );
+    let rows = labels_true.iter().max().map_or(0, |&m| m + 1);
+    let cols = labels_pred.iter().max().map_or(0, |&m| m + 1);
+    let mut table = vec![vec![0usize; cols]; rows];
+    for (&t, &p) in labels_true.iter().zip(labels_pred.iter()) {
+        table[t][p] += 1;
+    }
+    table
+}
+
+fn row_sums(table: &[Vec<usize>]) -> Vec<usize> {
+    table.iter().map(|row| row.iter().sum()).collect()
+}
+
+fn col_sums(table: &[Vec<usize>]) -> Vec<usize> {
+    let cols = table.first().map_or(0, |row| row.len());
+    (0..cols)
+        .map(|j| table.iter().map(|row| row[j]).sum())
+        .collect()
+}
+
+fn pairs(n: usize) -> f64 {
+    let n = n as f64;
+    n * (n - 1.0) / 2.0
+}
+
+fn entropy(counts: &[usize], n: usize) -> f64 {
+    let n = n as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / n;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+fn mutual_information(table: &[Vec<usize>], n: usize) -> f64 {
+    let rows = row_sums(table);
+    let cols = col_sums(table);
+    let n = n as f64;
+    let mut mi = 0.0;
+    for (i, row) in table.iter().enumerate() {
+        for (j, &nij) in row.iter().enumerate() {
+            if nij > 0 {
+                let nij = nij as f64;
+                mi += nij / n * (n * nij / (rows[i] as f64 * cols[j] as f64)).ln();
+            }
+        }
+    }
+    mi
+}
+
+/// Adjusted Rand index: 1 for identical partitions, around 0 for random ones.
+pub fn adjusted_rand_index(labels_true: &[usize], labels_pred: &[usize]) -> f64 {
+    let table = contingency_table(labels_true, labels_pred);
+    let n = labels_true.len();
+    let sum_cells: f64 = table.iter().flatten().map(|&c| pairs(c)).sum();
+    let sum_rows: f64 = row_sums(&table).into_iter().map(pairs).sum();
+    let sum_cols: f64 = col_sums(&table).into_iter().map(pairs).sum();
+    let expected = if n > 1 {
+        sum_rows * sum_cols / pairs(n)
+    } else {
+        0.0
+    };
+    let max_index = (sum_rows + sum_cols) / 2.0;
+    if max_index == expected {
+        return 1.0;
+    }
+    (sum_cells - expected) / (max_index - expected)
+}
+
+/// Mutual information normalized by the arithmetic mean of both entropies.
+pub fn normalized_mutual_info(labels_true: &[usize], labels_pred: &[usize]) -> f64 {
+    let table = contingency_table(labels_true, labels_pred);
+    let n = labels_true.len();
+    let h_true = entropy(&row_sums(&table), n);
+    let h_pred = entropy(&col_sums(&table), n);
+    if h_true == 0.0 && h_pred == 0.0 {
+        return 1.0;
+    }
+    let denom = (h_true + h_pred) / 2.0;
+    if denom == 0.0 {
+        return 0.0;
+    }
+    mutual_information(&table, n) / denom
+}
+
+/// Homogeneity, completeness and their harmonic mean (V-measure, beta = 1).
+pub fn homogeneity_completeness_v_measure(
+    labels_true: &[usize],
+    labels_pred: &[usize],
+) -> (f64, f64, f64) {
+    let table = contingency_table(labels_true, labels_pred);
+    let n = labels_true.len();
+    if n == 0 {
+        return (1.0, 1.0, 1.0);
+    }
+    let h_true = entropy(&row_sums(&table), n);
+    let h_pred = entropy(&col_sums(&table), n);
+    let mi = mutual_information(&table, n);
+    let homogeneity = if h_true > 0.0 { mi / h_true } else { 1.0 };
+    let completeness = if h_pred > 0.0 { mi / h_pred } else { 1.0 };
+    let v_measure = if homogeneity + completeness > 0.0 {
+        2.0 * homogeneity * completeness / (homogeneity + completeness)
+    } else {
+        0.0
+    };
+    (homogeneity, completeness, v_measure)
+}
+
+pub fn v_measure(labels_true: &[usize], labels_pred: &[usize]) -> f64 {
+    homogeneity_completeness_v_measure(labels_true, labels_pred).2
+}
+
+/// Fraction of points that belong to the majority true label of their
+/// predicted cluster.
+pub fn purity(labels_true: &[usize], labels_pred: &[usize]) -> f64 {
+    let table = contingency_table(labels_true, labels_pred);
+    let n = labels_true.len();
+    if n == 0 {
+        return 0.0;
+    }
+    let cols = col_sums(&table).len();
+    let majority: usize = (0..cols)
+        .map(|j| table.iter().map(|row| row[j]).max().unwrap_or(0))
+        .sum();
+    majority as f64 / n as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f64 = 1e-12;
+
+    // Contingency table [[2, 1, 0], [0, 1, 2]] over six points.
+    const TRUE: [usize; 6] = [0, 0, 0, 1, 1, 1];
+    const PRED: [usize; 6] = [0, 0, 1, 1, 2, 2];
+
+    #[test]
+    fn identical_labelings_score_one() {
+        let labels = [0, 0, 1, 1, 2, 2, 2];
+        assert!((adjusted_rand_index(&labels, &labels) - 1.0).abs() < EPS);
+        assert!((normalized_mutual_info(&labels, &labels) - 1.0).abs() < EPS);
+        assert!((v_measure(&labels, &labels) - 1.0).abs() < EPS);
+        assert!((purity(&labels, &labels) - 1.0).abs() < EPS);
+    }
+
+    #[test]
+    fn permuted_label_ids_score_one() {
+        let labels_true = [0, 0, 1, 1, 2, 2, 2];
+        let labels_pred = [2, 2, 0, 0, 1, 1, 1];
+        assert!((adjusted_rand_index(&labels_true, &labels_pred) - 1.0).abs() < EPS);
+        assert!((normalized_mutual_info(&labels_true, &labels_pred) - 1.0).abs() < EPS);
+        assert!((v_measure(&labels_true, &labels_pred) - 1.0).abs() < EPS);
+        assert!((purity(&labels_true, &labels_pred) - 1.0).abs() < EPS);
+    }
+
+    #[test]
+    fn contingency_counts_pairs_of_labels() {
+        assert_eq!(
+            contingency_table(&TRUE, &PRED),
+            vec![vec![2, 1, 0], vec![0, 1, 2]]
+        );
+    }
+
+    #[test]
+    fn hand_computed_scores() {
+        // Pairs: cells 2, rows 6, columns 3, all 15; expected 6 * 3 / 15.
+        let expected = 6.0 * 3.0 / 15.0;
+        let ari = (2.0 - expected) / (4.5 - expected);
+        assert!((adjusted_rand_index(&TRUE, &PRED) - ari).abs() < EPS);
+
+        // MI = 2/3 ln 2, H(true) = ln 2, H(pred) = ln 3.
+        let (ln2, ln3) = (2f64.ln(), 3f64.ln());
+        let mi = 2.0 / 3.0 * ln2;
+        let nmi = mi / ((ln2 + ln3) / 2.0);
+        assert!((normalized_mutual_info(&TRUE, &PRED) - nmi).abs() < EPS);
+
+        let (h, c, v) = homogeneity_completeness_v_measure(&TRUE, &PRED);
+        assert!((h - 2.0 / 3.0).abs() < EPS);
+        assert!((c - mi / ln3).abs() < EPS);
+        assert!((v - 2.0 * h * c / (h + c)).abs() < EPS);
+
+        // Majorities of the predicted clusters: 2 + 1 + 2.
+        assert!((purity(&TRUE, &PRED) - 5.0 / 6.0).abs() < EPS);
+    }
+}
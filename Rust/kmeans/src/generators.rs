@@ -0,0 +1,305 @@
+use crate::point::Point;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use rand_distr::{Normal, Uniform};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Generated points together with the cluster each point was drawn from.
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    pub points: Vec<Point>,
+    pub labels: Vec<usize>,
+}
+
+impl Dataset {
+    fn shuffled(mut self, rng: &mut StdRng) -> Self {
+        let mut order: Vec<usize> = (0..self.points.len()).collect();
+        order.shuffle(rng);
+        self.points = order.iter().map(|&i| self.points[i].clone()).collect();
+        self.labels = order.iter().map(|&i| self.labels[i]).collect();
+        self
+    }
+}
+
+// Splits n as evenly as possible over the weights; rounding leftovers go to
+// the first groups.
+fn split(n: usize, weights: &[f64]) -> Vec<usize> {
+    let total: f64 = weights.iter().sum();
+    let mut sizes: Vec<usize> = weights
+        .iter()
+        .map(|w| (n as f64 * w / total).floor() as usize)
+        .collect();
+    let mut left = n - sizes.iter().sum::<usize>();
+    for size in sizes.iter_mut() {
+        if left == 0 {
+            break;
+        }
+        *size += 1;
+        left -= 1;
+    }
+    sizes
+}
+
+/// Uniform noise over `[low, high)` in both coordinates; has no true clusters.
+pub fn uniform(n: usize, low: f64, high: f64, seed: u64) -> Vec<Point> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let dist = Uniform::new(low, high);
+    (0..n)
+        .map(|_| Point {
+            x: dist.sample(&mut rng),
+            y: dist.sample(&mut rng),
+        })
+        .collect()
+}
+
+pub fn random_centers(k: usize, center_box: (f64, f64), seed: u64) -> Vec<Point> {
+    uniform(k, center_box.0, center_box.1, seed)
+}
+
+fn gaussian_blobs(rng: &mut StdRng, sizes: &[usize], centers: &[Point], stds: &[f64]) -> Dataset {
+    let mut points = Vec::with_capacity(sizes.iter().sum());
+    let mut labels = Vec::with_capacity(points.capacity());
+    for (label, (&size, center)) in sizes.iter().zip(centers.iter()).enumerate() {
+        let std = if stds.len() == 1 {
+            stds[0]
+        } else {
+            stds[label]
+        };
+        let dx = Normal::new(center.x, std).unwrap();
+        let dy = Normal::new(center.y, std).unwrap();
+        for _ in 0..size {
+            points.push(Point {
+                x: dx.sample(rng),
+                y: dy.sample(rng),
+            });
+            labels.push(label);
+        }
+    }
+    Dataset { points, labels }
+}
+
+/// Isotropic Gaussian blobs, one per center, like scikit-learn's
+/// `make_blobs`. `stds` holds either one deviation for every blob or one
+/// per center.
+pub fn blobs(n: usize, centers: &[Point], stds: &[f64], seed: u64) -> Dataset {
+    assert!(
+        stds.len() == 1 || stds.len() == centers.len(),
+        "need one standard deviation or one per center"
+    );
+    let mut rng = StdRng::seed_from_u64(seed);
+    let sizes = split(n, &vec![1.0; centers.len()]);
+    gaussian_blobs(&mut rng, &sizes, centers, stds).shuffled(&mut rng)
+}
+
+/// `k` blobs with centers drawn uniformly from `center_box`; the defaults of
+/// the Python experiments are `k = 4`, `std = 1.5`, `center_box = (-10, 10)`.
+pub fn make_blobs(n: usize, k: usize, std: f64, center_box: (f64, f64), seed: u64) -> Dataset {
+    let centers = random_centers(k, center_box, seed);
+    blobs(n, &centers, &[std], seed.wrapping_add(1))
+}
+
+/// Blobs stretched by the linear map `transform` (row-major 2x2 matrix).
+pub fn anisotropic_blobs(
+    n: usize,
+    centers: &[Point],
+    std: f64,
+    transform: [[f64; 2]; 2],
+    seed: u64,
+) -> Dataset {
+    let mut data = blobs(n, centers, &[std], seed);
+    for p in data.points.iter_mut() {
+        let (x, y) = (p.x, p.y);
+        p.x = transform[0][0] * x + transform[0][1] * y;
+        p.y = transform[1][0] * x + transform[1][1] * y;
+    }
+    data
+}
+
+/// Blobs with their own spread and share of the points (`weights` are
+/// relative sizes).
+pub fn varied_density_blobs(
+    n: usize,
+    centers: &[Point],
+    stds: &[f64],
+    weights: &[f64],
+    seed: u64,
+) -> Dataset {
+    assert!(
+        stds.len() == centers.len() && weights.len() == centers.len(),
+        "need one standard deviation and one weight per center"
+    );
+    let mut rng = StdRng::seed_from_u64(seed);
+    let sizes = split(n, weights);
+    gaussian_blobs(&mut rng, &sizes, centers, stds).shuffled(&mut rng)
+}
+
+fn add_noise(data: &mut Dataset, noise: f64, rng: &mut StdRng) {
+    if noise > 0.0 {
+        let dist = Normal::new(0.0, noise).unwrap();
+        for p in data.points.iter_mut() {
+            p.x += dist.sample(rng);
+            p.y += dist.sample(rng);
+        }
+    }
+}
+
+/// Two interleaving half circles.
+pub fn moons(n: usize, noise: f64, seed: u64) -> Dataset {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let sizes = split(n, &[1.0, 1.0]);
+    let mut data = Dataset {
+        points: Vec::with_capacity(n),
+        labels: Vec::with_capacity(n),
+    };
+    for (label, &size) in sizes.iter().enumerate() {
+        for i in 0..size {
+            let t = PI * i as f64 / (size.max(2) - 1) as f64;
+            let point = if label == 0 {
+                Point {
+                    x: t.cos(),
+                    y: t.sin(),
+                }
+            } else {
+                Point {
+                    x: 1.0 - t.cos(),
+                    y: 0.5 - t.sin(),
+                }
+            };
+            data.points.push(point);
+            data.labels.push(label);
+        }
+    }
+    add_noise(&mut data, noise, &mut rng);
+    data.shuffled(&mut rng)
+}
+
+/// A large circle (label 0) around a smaller one (label 1) whose radius is
+/// `factor` times the outer radius.
+pub fn circles(n: usize, factor: f64, noise: f64, seed: u64) -> Dataset {
+    assert!((0.0..1.0).contains(&factor), "factor must be in [0, 1)");
+    let mut rng = StdRng::seed_from_u64(seed);
+    let sizes = split(n, &[1.0, 1.0]);
+    let mut data = Dataset {
+        points: Vec::with_capacity(n),
+        labels: Vec::with_capacity(n),
+    };
+    for (label, &size) in sizes.iter().enumerate() {
+        let radius = if label == 0 { 1.0 } else { factor };
+        for i in 0..size {
+            let t = 2.0 * PI * i as f64 / size as f64;
+            data.points.push(Point {
+                x: radius * t.cos(),
+                y: radius * t.sin(),
+            });
+            data.labels.push(label);
+        }
+    }
+    add_noise(&mut data, noise, &mut rng);
+    data.shuffled(&mut rng)
+}
+
+/// A generator and its parameters, as named in experiment spec files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DatasetSpec {
+    Uniform {
+        low: f64,
+        high: f64,
+    },
+    Blobs {
+        centers: usize,
+        std: f64,
+        center_box: (f64, f64),
+    },
+    Moons {
+        noise: f64,
+    },
+    Circles {
+        factor: f64,
+        noise: f64,
+    },
+}
+
+impl Default for DatasetSpec {
+    fn default() -> Self {
+        DatasetSpec::Blobs {
+            centers: 4,
+            std: 1.5,
+            center_box: (-10.0, 10.0),
+        }
+    }
+}
+
+/// `n` rows of `dim` coordinates uniform over `[low, high)`, row-major.
+pub fn uniform_nd(n: usize, dim: usize, low: f64, high: f64, seed: u64) -> Vec<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let dist = Uniform::new(low, high);
+    (0..n * dim).map(|_| dist.sample(&mut rng)).collect()
+}
+
+/// `make_blobs` in `dim` dimensions: `k` isotropic blobs with centers drawn
+/// from `center_box` in every coordinate, as `n` row-major rows.
+pub fn make_blobs_nd(
+    n: usize,
+    dim: usize,
+    k: usize,
+    std: f64,
+    center_box: (f64, f64),
+    seed: u64,
+) -> Vec<f64> {
+    let centers = uniform_nd(k, dim, center_box.0, center_box.1, seed);
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(1));
+    let mut labels: Vec<usize> = split(n, &vec![1.0; k])
+        .into_iter()
+        .enumerate()
+        .flat_map(|(label, size)| std::iter::repeat_n(label, size))
+        .collect();
+    labels.shuffle(&mut rng);
+    let noise = Normal::new(0.0, std).unwrap();
+    let mut values = Vec::with_capacity(n * dim);
+    for label in labels {
+        for c in &centers[label * dim..(label + 1) * dim] {
+            values.push(c + noise.sample(&mut rng));
+        }
+    }
+    values
+}
+
+impl DatasetSpec {
+    /// Whether [`DatasetSpec::generate_nd`] supports this dataset; moons and
+    /// circles only exist in two dimensions.
+    pub fn has_nd(&self) -> bool {
+        matches!(
+            self,
+            DatasetSpec::Uniform { .. } | DatasetSpec::Blobs { .. }
+        )
+    }
+
+    /// `n` row-major points of `dim` coordinates, or `None` for datasets
+    /// that are two-dimensional by construction.
+    pub fn generate_nd(&self, n: usize, dim: usize, seed: u64) -> Option<Vec<f64>> {
+        match *self {
+            DatasetSpec::Uniform { low, high } => Some(uniform_nd(n, dim, low, high, seed)),
+            DatasetSpec::Blobs {
+                centers,
+                std,
+                center_box,
+            } => Some(make_blobs_nd(n, dim, centers, std, center_box, seed)),
+            DatasetSpec::Moons { .. } | DatasetSpec::Circles { .. } => None,
+        }
+    }
+
+    pub fn generate(&self, n: usize, seed: u64) -> Vec<Point> {
+        match *self {
+            DatasetSpec::Uniform { low, high } => uniform(n, low, high, seed),
+            DatasetSpec::Blobs {
+                centers,
+                std,
+                center_box,
+            } => make_blobs(n, centers, std, center_box, seed).points,
+            DatasetSpec::Moons { noise } => moons(n, noise, seed).points,
+            DatasetSpec::Circles { factor, noise } => circles(n, factor, noise, seed).points,
+        }
+    }
+}
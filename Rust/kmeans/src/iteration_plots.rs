@@ -0,0 +1,109 @@
+use crate::error::{Error, Result};
+use plotters::prelude::*;
+use serde_json::Value;
+use std::fs::{create_dir_all, File};
+use std::io::BufReader;
+use std::ops::Range;
+use std::path::Path;
+
+fn coords(value: &Value) -> Result<(f64, f64)> {
+    match (value[0].as_f64(), value[1].as_f64()) {
+        (Some(x), Some(y)) => Ok((x, y)),
+        _ => Err(Error::InvalidFormat(format!(
+            "expected an [x, y] pair, found {}",
+            value
+        ))),
+    }
+}
+
+fn array<'a>(value: &'a Value, what: &str) -> Result<&'a Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| Error::InvalidFormat(format!("log has no '{}' array", what)))
+}
+
+// Data range padded by 5% on each side; falls back to 0..100 without points.
+fn bounds(points: &[(f64, f64)]) -> (Range<f64>, Range<f64>) {
+    let pad = |lo: f64, hi: f64| {
+        if lo > hi {
+            return 0.0..100.0;
+        }
+        let margin = ((hi - lo) * 0.05).max(1e-9);
+        (lo - margin)..(hi + margin)
+    };
+    let (x_min, x_max, y_min, y_max) = points.iter().fold(
+        (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+        |(x0, x1, y0, y1), &(x, y)| (x0.min(x), x1.max(x), y0.min(y), y1.max(y)),
+    );
+    (pad(x_min, x_max), pad(y_min, y_max))
+}
+
+/// Renders one PNG per iteration of a log written by `kmeans_seq_log` into
+/// `out_dir` and returns the number of images.
+pub fn plot_iterations(log_path: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> Result<usize> {
+    let file = File::open(log_path)?;
+    let reader = BufReader::new(file);
+    let data: Value = serde_json::from_reader(reader)?;
+
+    let points = array(&data["points"], "points")?
+        .iter()
+        .map(coords)
+        .collect::<Result<Vec<_>>>()?;
+    let iterations = array(&data["iterations"], "iterations")?;
+
+    let (x_range, y_range) = bounds(&points);
+
+    // Boje za klastere
+    let colors = [&RED, &BLUE, &GREEN, &MAGENTA, &CYAN, &YELLOW, &BLACK];
+
+    let out_dir = out_dir.as_ref();
+    create_dir_all(out_dir)?;
+
+    for (iter_idx, iter) in iterations.iter().enumerate() {
+        let centroids = array(&iter["centroids"], "centroids")?;
+        let labels = array(&iter["labels"], "labels")?;
+
+        let filename = out_dir.join(format!("iter_{}.png", iter_idx));
+        let root = BitMapBackend::new(&filename, (800, 800)).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(&root)
+            .margin(20)
+            .caption(format!("KMeans Iteration {iter_idx}"), ("sans-serif", 30))
+            .x_label_area_size(30)
+            .y_label_area_size(30)
+            .build_cartesian_2d(x_range.clone(), y_range.clone())?;
+
+        chart.configure_mesh().draw()?;
+
+        // Crtanje tacaka
+        for (i, &(x, y)) in points.iter().enumerate() {
+            let label = labels
+                .get(i)
+                .and_then(Value::as_u64)
+                .ok_or_else(|| Error::InvalidFormat(format!("missing label for point {}", i)))?
+                as usize;
+            let color = colors[label % colors.len()];
+            chart.draw_series(PointSeries::of_element(
+                vec![(x, y)],
+                2,
+                color,
+                &|c, s, st| EmptyElement::at(c) + Circle::new((0, 0), s, st.filled()),
+            ))?;
+        }
+
+        // Crtanje centroida
+        for (j, c) in centroids.iter().enumerate() {
+            let (x, y) = coords(c)?;
+            let color = colors[j % colors.len()];
+            chart.draw_series(PointSeries::of_element(
+                vec![(x, y)],
+                8,
+                color,
+                &|c, s, st| EmptyElement::at(c) + Circle::new((0, 0), s, st.filled()),
+            ))?;
+        }
+        root.present()?;
+    }
+    Ok(iterations.len())
+}
@@ -0,0 +1,172 @@
+use crate::error::{Error, Result};
+use crate::kmeans_seq::update_centroids;
+use crate::npy::{write_npy_header, Dtype};
+use crate::point::{nearest_centroid, Point};
+use crate::point_file::{PointFileHeader, HEADER_LEN};
+use rand::prelude::*;
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Streams a point file chunk by chunk, calling `f` with each decoded chunk.
+fn for_each_chunk(
+    path: &Path,
+    chunk_size: usize,
+    mut f: impl FnMut(&[Point]) -> Result<()>,
+) -> Result<()> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let header = PointFileHeader::read_from(&mut reader)?;
+    let mut remaining = header.n as usize;
+    let row_len = header.row_len()?;
+    let mut buffer = vec![0u8; chunk_size.min(remaining) * row_len];
+    while remaining > 0 {
+        let rows = remaining.min(chunk_size);
+        let bytes = &mut buffer[..rows * row_len];
+        reader.read_exact(bytes)?;
+        f(&header.decode_rows(bytes)?)?;
+        remaining -= rows;
+    }
+    Ok(())
+}
+
+fn sample_centroids(path: &Path, header: &PointFileHeader, k: usize) -> Result<Vec<Point>> {
+    let mut file = File::open(path)?;
+    let indices = rand::seq::index::sample(&mut thread_rng(), header.n as usize, k);
+    let row_len = header.row_len()?;
+    let mut row = vec![0u8; row_len];
+    let mut centroids = Vec::with_capacity(k);
+    for i in indices {
+        file.seek(SeekFrom::Start((HEADER_LEN + i * row_len) as u64))?;
+        file.read_exact(&mut row)?;
+        centroids.extend(header.decode_rows(&row)?);
+    }
+    Ok(centroids)
+}
+
+/// Lloyd's iteration over a point file that is never fully loaded: every
+/// iteration streams `chunk_size` rows at a time and reduces per-chunk sums
+/// in parallel. After the last iteration the points are assigned to the
+/// final centroids and the labels are written to `labels_path` as an int64
+/// `.npy` array.
+pub fn kmeans_chunked(
+    path: impl AsRef<Path>,
+    labels_path: impl AsRef<Path>,
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    chunk_size: usize,
+    initial_centroids: Option<Vec<Point>>,
+) -> Result<Vec<Point>> {
+    let path = path.as_ref();
+    let header = PointFileHeader::read_from(File::open(path)?)?;
+    if (header.n as usize) < k || chunk_size == 0 {
+        return Err(Error::InvalidFormat(format!(
+            "cannot cluster {} points into {} clusters with chunk size {}",
+            header.n, k, chunk_size
+        )));
+    }
+    let mut centroids = match initial_centroids {
+        Some(centroids) if centroids.len() != k => {
+            return Err(Error::InvalidFormat(format!(
+                "expected {} initial centroids, got {}",
+                k,
+                centroids.len()
+            )));
+        }
+        Some(centroids) => centroids,
+        None => sample_centroids(path, &header, k)?,
+    };
+
+    for _ in 0..max_iters {
+        let mut sums = vec![Point::zero(); k];
+        let mut counts = vec![0usize; k];
+        for_each_chunk(path, chunk_size, |chunk| {
+            let (chunk_sums, chunk_counts) = chunk
+                .par_iter()
+                .fold(
+                    || (vec![Point::zero(); k], vec![0usize; k]),
+                    |mut acc, point| {
+                        let cluster = nearest_centroid(point, &centroids);
+                        acc.0[cluster] = acc.0[cluster].add(point);
+                        acc.1[cluster] += 1;
+                        acc
+                    },
+                )
+                .reduce(
+                    || (vec![Point::zero(); k], vec![0usize; k]),
+                    |(mut sums1, mut counts1), (sums2, counts2)| {
+                        for j in 0..k {
+                            sums1[j] = sums1[j].add(&sums2[j]);
+                            counts1[j] += counts2[j];
+                        }
+                        (sums1, counts1)
+                    },
+                );
+            for j in 0..k {
+                sums[j] = sums[j].add(&chunk_sums[j]);
+                counts[j] += chunk_counts[j];
+            }
+            Ok(())
+        })?;
+
+        let max_shift = update_centroids(&mut centroids, &sums, &counts);
+        if max_shift < tolerance {
+            break;
+        }
+    }
+
+    let mut labels = BufWriter::new(File::create(labels_path)?);
+    write_npy_header(&mut labels, &[header.n as usize], Dtype::I64)?;
+    for_each_chunk(path, chunk_size, |chunk| {
+        let assignments: Vec<usize> = chunk
+            .par_iter()
+            .map(|point| nearest_centroid(point, &centroids))
+            .collect();
+        for cluster in assignments {
+            labels.write_all(&(cluster as i64).to_le_bytes())?;
+        }
+        Ok(())
+    })?;
+    labels.flush()?;
+    Ok(centroids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::make_blobs;
+    use crate::kmeans_seq::kmeans_seq;
+    use crate::npy::read_npy;
+    use crate::point_file::write_point_file;
+
+    #[test]
+    fn matches_sequential_lloyd() {
+        let dir = std::env::temp_dir();
+        let id = std::process::id();
+        let points_path = dir.join(format!("kmeans-{}-chunked.pts", id));
+        let labels_path = dir.join(format!("kmeans-{}-chunked.npy", id));
+        let points = make_blobs(1000, 3, 1.0, (-10.0, 10.0), 3).points;
+        let initial = points[..3].to_vec();
+        write_point_file(&points_path, &points).unwrap();
+        let centroids = kmeans_chunked(
+            &points_path,
+            &labels_path,
+            3,
+            50,
+            1e-9,
+            128,
+            Some(initial.clone()),
+        )
+        .unwrap();
+        let labels = read_npy(&labels_path).unwrap().to_labels().unwrap();
+        std::fs::remove_file(&points_path).unwrap();
+        std::fs::remove_file(&labels_path).unwrap();
+
+        let (expected_centroids, expected_labels) = kmeans_seq(&points, 3, 50, 1e-9, Some(initial));
+        assert_eq!(labels, expected_labels);
+        for (c, e) in centroids.iter().zip(&expected_centroids) {
+            assert!((c.x - e.x).abs() < 1e-9 && (c.y - e.y).abs() < 1e-9);
+        }
+    }
+}
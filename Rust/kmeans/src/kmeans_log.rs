@@ -1,8 +1,10 @@
-use crate::point::{euclidean_distance, Point};
+use crate::error::Result;
+use crate::point::{euclidean_distance, nearest_centroid, Point};
 use rand::prelude::*;
 use serde_json::json;
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 
 pub fn kmeans_seq_log(
     points: &[Point],
@@ -10,8 +12,8 @@ pub fn kmeans_seq_log(
     max_iters: usize,
     tolerance: f64,
     initial_centroids: Option<Vec<Point>>,
-    json_path: &str,
-) -> (Vec<Point>, Vec<usize>) {
+    json_path: impl AsRef<Path>,
+) -> Result<(Vec<Point>, Vec<usize>)> {
     let mut rng = thread_rng();
     let mut centroids: Vec<Point> = match initial_centroids {
         Some(centroids) => centroids,
@@ -26,16 +28,7 @@ pub fn kmeans_seq_log(
 
     for i in 0..max_iters {
         for (i, point) in points.iter().enumerate() {
-            let mut min_dist = f64::MAX;
-            let mut cluster = 0;
-            for (j, centroid) in centroids.iter().enumerate() {
-                let dist = euclidean_distance(point, centroid);
-                if dist < min_dist {
-                    min_dist = dist;
-                    cluster = j;
-                }
-            }
-            assignments[i] = cluster;
+            assignments[i] = nearest_centroid(point, &centroids);
         }
 
         let mut sums = vec![Point::zero(); k];
@@ -70,9 +63,8 @@ pub fn kmeans_seq_log(
             break;
         }
     }
-    let mut file = File::create(json_path).expect("Cannot create JSON file");
-    let json_str = serde_json::to_string_pretty(&json_obj).unwrap();
-    file.write_all(json_str.as_bytes())
-        .expect("Cannot write JSON");
-    (centroids, assignments)
+    let mut file = File::create(json_path)?;
+    let json_str = serde_json::to_string_pretty(&json_obj)?;
+    file.write_all(json_str.as_bytes())?;
+    Ok((centroids, assignments))
 }
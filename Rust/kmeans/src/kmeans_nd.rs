@@ -0,0 +1,180 @@
+//! Lloyd's algorithm for points with any number of coordinates, stored
+//! row-major in one flat slice. The dimension sweep uses it; everything else
+//! in the crate works on two-dimensional [`Point`](crate::point::Point)s.
+
+use rayon::prelude::*;
+
+/// Result of [`kmeans_nd_seq`] or [`kmeans_nd_par`]; `centroids` holds `k`
+/// row-major rows of the input dimension.
+#[derive(Debug, Clone)]
+pub struct NdRun {
+    pub centroids: Vec<f64>,
+    pub assignments: Vec<usize>,
+    pub iterations: usize,
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+fn nearest(point: &[f64], centroids: &[f64]) -> usize {
+    centroids
+        .chunks_exact(point.len())
+        .map(|c| squared_distance(point, c))
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(0, |(i, _)| i)
+}
+
+// Per-cluster coordinate sums (k rows of `dim`) and point counts.
+fn accumulate(data: &[f64], dim: usize, assignments: &[usize], k: usize) -> (Vec<f64>, Vec<usize>) {
+    let mut sums = vec![0.0; k * dim];
+    let mut counts = vec![0usize; k];
+    for (point, &cluster) in data.chunks_exact(dim).zip(assignments) {
+        for (s, x) in sums[cluster * dim..(cluster + 1) * dim]
+            .iter_mut()
+            .zip(point)
+        {
+            *s += x;
+        }
+        counts[cluster] += 1;
+    }
+    (sums, counts)
+}
+
+fn accumulate_par(
+    data: &[f64],
+    dim: usize,
+    assignments: &[usize],
+    k: usize,
+) -> (Vec<f64>, Vec<usize>) {
+    data.par_chunks_exact(dim)
+        .zip(assignments.par_iter())
+        .fold(
+            || (vec![0.0; k * dim], vec![0usize; k]),
+            |mut acc, (point, &cluster)| {
+                for (s, x) in acc.0[cluster * dim..(cluster + 1) * dim]
+                    .iter_mut()
+                    .zip(point)
+                {
+                    *s += x;
+                }
+                acc.1[cluster] += 1;
+                acc
+            },
+        )
+        .reduce(
+            || (vec![0.0; k * dim], vec![0usize; k]),
+            |(mut sums1, mut counts1), (sums2, counts2)| {
+                sums1.iter_mut().zip(&sums2).for_each(|(a, b)| *a += b);
+                counts1.iter_mut().zip(&counts2).for_each(|(a, b)| *a += b);
+                (sums1, counts1)
+            },
+        )
+}
+
+// Moves every non-empty cluster's centroid to the mean of its points and
+// returns the largest shift.
+fn update_centroids(centroids: &mut [f64], dim: usize, sums: &[f64], counts: &[usize]) -> f64 {
+    let mut max_shift: f64 = 0.0;
+    for (j, centroid) in centroids.chunks_exact_mut(dim).enumerate() {
+        if counts[j] > 0 {
+            let mut shift = 0.0;
+            for (c, s) in centroid.iter_mut().zip(&sums[j * dim..(j + 1) * dim]) {
+                let new = s / counts[j] as f64;
+                shift += (new - *c) * (new - *c);
+                *c = new;
+            }
+            max_shift = max_shift.max(shift.sqrt());
+        }
+    }
+    max_shift
+}
+
+fn lloyd(
+    data: &[f64],
+    dim: usize,
+    max_iters: usize,
+    tolerance: f64,
+    mut centroids: Vec<f64>,
+    parallel: bool,
+) -> NdRun {
+    assert!(
+        dim > 0 && data.len().is_multiple_of(dim) && centroids.len().is_multiple_of(dim),
+        "data and centroids must be whole rows of {} values",
+        dim
+    );
+    let k = centroids.len() / dim;
+    let mut assignments = vec![0; data.len() / dim];
+    let mut iterations = 0;
+    for _ in 0..max_iters {
+        iterations += 1;
+        let (sums, counts) = if parallel {
+            assignments
+                .par_iter_mut()
+                .zip(data.par_chunks_exact(dim))
+                .for_each(|(a, point)| *a = nearest(point, &centroids));
+            accumulate_par(data, dim, &assignments, k)
+        } else {
+            for (a, point) in assignments.iter_mut().zip(data.chunks_exact(dim)) {
+                *a = nearest(point, &centroids);
+            }
+            accumulate(data, dim, &assignments, k)
+        };
+        if update_centroids(&mut centroids, dim, &sums, &counts) < tolerance {
+            break;
+        }
+    }
+    NdRun {
+        centroids,
+        assignments,
+        iterations,
+    }
+}
+
+/// Sequential Lloyd iterations over `data`, `dim` values per point, starting
+/// from `initial_centroids` (k rows of `dim`).
+pub fn kmeans_nd_seq(
+    data: &[f64],
+    dim: usize,
+    max_iters: usize,
+    tolerance: f64,
+    initial_centroids: Vec<f64>,
+) -> NdRun {
+    lloyd(data, dim, max_iters, tolerance, initial_centroids, false)
+}
+
+/// [`kmeans_nd_seq`] with the assignment and accumulation steps run on the
+/// current rayon pool.
+pub fn kmeans_nd_par(
+    data: &[f64],
+    dim: usize,
+    max_iters: usize,
+    tolerance: f64,
+    initial_centroids: Vec<f64>,
+) -> NdRun {
+    lloyd(data, dim, max_iters, tolerance, initial_centroids, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separates_distant_groups() {
+        // Two groups of three points in three dimensions.
+        let data = [
+            0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, //
+            10.0, 10.0, 10.0, 11.0, 10.0, 10.0, 10.0, 11.0, 10.0,
+        ];
+        let initial = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+        let seq = kmeans_nd_seq(&data, 3, 10, 1e-9, initial.clone());
+        let par = kmeans_nd_par(&data, 3, 10, 1e-9, initial);
+        assert_eq!(seq.assignments, vec![0, 0, 0, 1, 1, 1]);
+        assert_eq!(seq.assignments, par.assignments);
+        let expected = [1.0 / 3.0, 1.0 / 3.0, 0.0, 31.0 / 3.0, 31.0 / 3.0, 10.0];
+        for (c, e) in seq.centroids.iter().zip(expected) {
+            assert!((c - e).abs() < 1e-12);
+        }
+    }
+}
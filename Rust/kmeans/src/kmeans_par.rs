@@ -1,4 +1,6 @@
-use crate::point::{euclidean_distance, Point};
+use crate::kmeans_run::{IterationTimings, KMeansRun, PhaseTimer};
+use crate::kmeans_seq::update_centroids;
+use crate::point::{nearest_centroid, Point};
 use rand::prelude::*;
 use rand::seq::SliceRandom;
 use rayon::prelude::*;
@@ -10,53 +12,58 @@ pub fn kmeans_par(
     tolerance: f64,
     initial_centroids: Option<Vec<Point>>,
 ) -> (Vec<Point>, Vec<usize>) {
+    let run = kmeans_par_run(points, k, max_iters, tolerance, initial_centroids);
+    (run.centroids, run.assignments)
+}
+
+pub fn kmeans_par_run(
+    points: &[Point],
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    initial_centroids: Option<Vec<Point>>,
+) -> KMeansRun {
+    lloyd(points, k, max_iters, tolerance, initial_centroids, false)
+}
+
+/// Like [`kmeans_par_run`], but also records how long initialization and
+/// each phase of every iteration took in [`KMeansRun::timings`].
+pub fn kmeans_par_timed(
+    points: &[Point],
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    initial_centroids: Option<Vec<Point>>,
+) -> KMeansRun {
+    lloyd(points, k, max_iters, tolerance, initial_centroids, true)
+}
+
+fn lloyd(
+    points: &[Point],
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    initial_centroids: Option<Vec<Point>>,
+    timed: bool,
+) -> KMeansRun {
+    let mut timer = PhaseTimer::new(timed);
     let mut rng = thread_rng();
     let mut centroids: Vec<Point> = match initial_centroids {
         Some(centroids) => centroids,
         None => points.choose_multiple(&mut rng, k).cloned().collect(),
     };
     let mut assignments = vec![0; points.len()];
+    let mut iterations = 0;
+    timer.init_done();
 
-    for i in 0..max_iters {
-        assignments
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(i, assign)| {
-                let point = &points[i];
-                let cluster = centroids
-                    .iter()
-                    .enumerate()
-                    .min_by(|(_, a), (_, b)| {
-                        euclidean_distance(point, a)
-                            .partial_cmp(&euclidean_distance(point, b))
-                            .unwrap()
-                    })
-                    .map(|(j, _)| j)
-                    .unwrap_or(0);
-                *assign = cluster;
-            });
+    for _ in 0..max_iters {
+        iterations += 1;
+        timer.lap();
+        assign_par(points, &centroids, &mut assignments);
 
-        let (sums, counts) = points
-            .par_iter()
-            .zip(assignments.par_iter())
-            .fold(
-                || (vec![Point::zero(); k], vec![0usize; k]),
-                |mut acc, (point, &cluster)| {
-                    acc.0[cluster] = acc.0[cluster].add(point);
-                    acc.1[cluster] += 1;
-                    acc
-                },
-            )
-            .reduce(
-                || (vec![Point::zero(); k], vec![0usize; k]),
-                |(mut sums1, mut counts1), (sums2, counts2)| {
-                    for j in 0..k {
-                        sums1[j] = sums1[j].add(&sums2[j]);
-                        counts1[j] += counts2[j];
-                    }
-                    (sums1, counts1)
-                },
-            );
+        let assignment = timer.lap();
+
+        let (sums, counts) = accumulate_par(points, &assignments, k);
 
         // let mut sums = vec![Point::zero(); k];
         // let mut counts = vec![0usize; k];
@@ -68,22 +75,62 @@ pub fn kmeans_par(
         //         counts[cluster] += 1;
         //     });
 
-        let mut max_shift = 0.0;
-        for j in 0..k {
-            if counts[j] > 0 {
-                let new_centroid = sums[j].div(counts[j] as f64);
-                let shift = euclidean_distance(&centroids[j], &new_centroid);
-                if shift > max_shift {
-                    max_shift = shift;
-                }
-                centroids[j] = new_centroid;
-            }
-        }
-        println!("Iteration {i}, shift = {max_shift}");
+        let accumulation = timer.lap();
 
+        let max_shift = update_centroids(&mut centroids, &sums, &counts);
+        let update = timer.lap();
+        timer.push(IterationTimings {
+            assignment,
+            accumulation,
+            update,
+        });
         if max_shift < tolerance {
             break;
         }
     }
-    (centroids, assignments)
+    KMeansRun {
+        centroids,
+        assignments,
+        iterations,
+        timings: timer.finish(),
+    }
+}
+
+/// Parallel assignment step: stores the nearest centroid of every point.
+pub fn assign_par(points: &[Point], centroids: &[Point], assignments: &mut [usize]) {
+    assignments
+        .par_iter_mut()
+        .enumerate()
+        .for_each(|(i, assign)| {
+            *assign = nearest_centroid(&points[i], centroids);
+        });
+}
+
+/// Per-cluster sums and counts, folded per rayon task and then reduced.
+pub fn accumulate_par(
+    points: &[Point],
+    assignments: &[usize],
+    k: usize,
+) -> (Vec<Point>, Vec<usize>) {
+    points
+        .par_iter()
+        .zip(assignments.par_iter())
+        .fold(
+            || (vec![Point::zero(); k], vec![0usize; k]),
+            |mut acc, (point, &cluster)| {
+                acc.0[cluster] = acc.0[cluster].add(point);
+                acc.1[cluster] += 1;
+                acc
+            },
+        )
+        .reduce(
+            || (vec![Point::zero(); k], vec![0usize; k]),
+            |(mut sums1, mut counts1), (sums2, counts2)| {
+                for j in 0..k {
+                    sums1[j] = sums1[j].add(&sums2[j]);
+                    counts1[j] += counts2[j];
+                }
+                (sums1, counts1)
+            },
+        )
 }
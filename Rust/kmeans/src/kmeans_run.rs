@@ -0,0 +1,117 @@
+use crate::point::Point;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Result of a single k-means run, with what the plain `(centroids,
+/// assignments)` tuple leaves out.
+#[derive(Debug, Clone)]
+pub struct KMeansRun {
+    pub centroids: Vec<Point>,
+    pub assignments: Vec<usize>,
+    /// Number of Lloyd iterations actually executed.
+    pub iterations: usize,
+    /// Per-phase wall-clock times, present only for the `_timed` variants.
+    pub timings: Option<PhaseTimings>,
+}
+
+/// Seconds spent in each phase of one Lloyd iteration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct IterationTimings {
+    /// Finding the nearest centroid of every point.
+    pub assignment: f64,
+    /// Summing points per cluster, including the rayon reduce.
+    pub accumulation: f64,
+    /// Dividing the sums and measuring the centroid shift.
+    pub update: f64,
+}
+
+/// Phase breakdown of a whole run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseTimings {
+    /// Seconds spent choosing initial centroids and allocating buffers.
+    pub init: f64,
+    pub iterations: Vec<IterationTimings>,
+}
+
+/// Seconds per phase summed over a run (or averaged over several runs).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseTotals {
+    pub init: f64,
+    pub assignment: f64,
+    pub accumulation: f64,
+    pub update: f64,
+}
+
+impl PhaseTimings {
+    pub fn totals(&self) -> PhaseTotals {
+        let mut totals = PhaseTotals {
+            init: self.init,
+            ..PhaseTotals::default()
+        };
+        for it in &self.iterations {
+            totals.assignment += it.assignment;
+            totals.accumulation += it.accumulation;
+            totals.update += it.update;
+        }
+        totals
+    }
+}
+
+impl PhaseTotals {
+    /// Component-wise mean of several runs' totals.
+    pub fn mean(totals: &[PhaseTotals]) -> PhaseTotals {
+        if totals.is_empty() {
+            return PhaseTotals::default();
+        }
+        let n = totals.len() as f64;
+        PhaseTotals {
+            init: totals.iter().map(|t| t.init).sum::<f64>() / n,
+            assignment: totals.iter().map(|t| t.assignment).sum::<f64>() / n,
+            accumulation: totals.iter().map(|t| t.accumulation).sum::<f64>() / n,
+            update: totals.iter().map(|t| t.update).sum::<f64>() / n,
+        }
+    }
+}
+
+// Lap timer used by the Lloyd loops. When disabled every call is a no-op, so
+// the untimed variants pay nothing beyond a branch.
+pub(crate) struct PhaseTimer {
+    last: Option<Instant>,
+    timings: PhaseTimings,
+}
+
+impl PhaseTimer {
+    pub(crate) fn new(enabled: bool) -> Self {
+        PhaseTimer {
+            last: enabled.then(Instant::now),
+            timings: PhaseTimings::default(),
+        }
+    }
+
+    // Seconds since the previous lap (or since `new`), restarting the clock.
+    pub(crate) fn lap(&mut self) -> f64 {
+        match &mut self.last {
+            Some(last) => {
+                let now = Instant::now();
+                let elapsed = now.duration_since(*last).as_secs_f64();
+                *last = now;
+                elapsed
+            }
+            None => 0.0,
+        }
+    }
+
+    pub(crate) fn init_done(&mut self) {
+        self.timings.init = self.lap();
+    }
+
+    pub(crate) fn push(&mut self, iteration: IterationTimings) {
+        if self.last.is_some() {
+            self.timings.iterations.push(iteration);
+        }
+    }
+
+    pub(crate) fn finish(self) -> Option<PhaseTimings> {
+        self.last.map(|_| self.timings)
+    }
+}
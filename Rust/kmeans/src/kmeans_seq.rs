@@ -1,4 +1,5 @@
-use crate::point::{euclidean_distance, Point};
+use crate::kmeans_run::{IterationTimings, KMeansRun, PhaseTimer};
+use crate::point::{euclidean_distance, nearest_centroid, Point};
 use rand::prelude::*;
 use rand::seq::SliceRandom;
 
@@ -9,49 +10,111 @@ pub fn kmeans_seq(
     tolerance: f64,
     initial_centroids: Option<Vec<Point>>,
 ) -> (Vec<Point>, Vec<usize>) {
+    let run = kmeans_seq_run(points, k, max_iters, tolerance, initial_centroids);
+    (run.centroids, run.assignments)
+}
+
+pub fn kmeans_seq_run(
+    points: &[Point],
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    initial_centroids: Option<Vec<Point>>,
+) -> KMeansRun {
+    lloyd(points, k, max_iters, tolerance, initial_centroids, false)
+}
+
+/// Like [`kmeans_seq_run`], but also records how long initialization and
+/// each phase of every iteration took in [`KMeansRun::timings`].
+pub fn kmeans_seq_timed(
+    points: &[Point],
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    initial_centroids: Option<Vec<Point>>,
+) -> KMeansRun {
+    lloyd(points, k, max_iters, tolerance, initial_centroids, true)
+}
+
+fn lloyd(
+    points: &[Point],
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    initial_centroids: Option<Vec<Point>>,
+    timed: bool,
+) -> KMeansRun {
+    let mut timer = PhaseTimer::new(timed);
     let mut rng = thread_rng();
     let mut centroids: Vec<Point> = match initial_centroids {
         Some(centroids) => centroids,
         None => points.choose_multiple(&mut rng, k).cloned().collect(),
     };
     let mut assignments = vec![0; points.len()];
+    let mut iterations = 0;
+    timer.init_done();
 
-    for i in 0..max_iters {
-        for (i, point) in points.iter().enumerate() {
-            let mut min_dist = f64::MAX;
-            let mut cluster = 0;
-            for (j, centroid) in centroids.iter().enumerate() {
-                let dist = euclidean_distance(point, centroid);
-                if dist < min_dist {
-                    min_dist = dist;
-                    cluster = j;
-                }
-            }
-            assignments[i] = cluster;
-        }
+    for _ in 0..max_iters {
+        iterations += 1;
+        timer.lap();
+        assign(points, &centroids, &mut assignments);
 
-        let mut sums = vec![Point::zero(); k];
-        let mut counts = vec![0usize; k];
-        for (point, &cluster) in points.iter().zip(assignments.iter()) {
-            sums[cluster] = sums[cluster].add(point);
-            counts[cluster] += 1;
-        }
+        let assignment = timer.lap();
 
-        let mut max_shift = 0.0;
-        for j in 0..k {
-            if counts[j] > 0 {
-                let new_centroid = sums[j].div(counts[j] as f64);
-                let shift = euclidean_distance(&centroids[j], &new_centroid);
-                if shift > max_shift {
-                    max_shift = shift;
-                }
-                centroids[j] = new_centroid;
-            }
-        }
-        println!("Iteration {i}, shift = {max_shift}");
+        let (sums, counts) = accumulate(points, &assignments, k);
+
+        let accumulation = timer.lap();
+
+        let max_shift = update_centroids(&mut centroids, &sums, &counts);
+        let update = timer.lap();
+        timer.push(IterationTimings {
+            assignment,
+            accumulation,
+            update,
+        });
         if max_shift < tolerance {
             break;
         }
     }
-    (centroids, assignments)
+    KMeansRun {
+        centroids,
+        assignments,
+        iterations,
+        timings: timer.finish(),
+    }
+}
+
+/// Assignment step: stores the nearest centroid of every point.
+pub fn assign(points: &[Point], centroids: &[Point], assignments: &mut [usize]) {
+    for (i, point) in points.iter().enumerate() {
+        assignments[i] = nearest_centroid(point, centroids);
+    }
+}
+
+/// Per-cluster coordinate sums and point counts.
+pub fn accumulate(points: &[Point], assignments: &[usize], k: usize) -> (Vec<Point>, Vec<usize>) {
+    let mut sums = vec![Point::zero(); k];
+    let mut counts = vec![0usize; k];
+    for (point, &cluster) in points.iter().zip(assignments.iter()) {
+        sums[cluster] = sums[cluster].add(point);
+        counts[cluster] += 1;
+    }
+    (sums, counts)
+}
+
+/// Update step: moves every non-empty cluster's centroid to the mean of its
+/// points and returns the largest shift.
+pub fn update_centroids(centroids: &mut [Point], sums: &[Point], counts: &[usize]) -> f64 {
+    let mut max_shift = 0.0;
+    for j in 0..centroids.len() {
+        if counts[j] > 0 {
+            let new_centroid = sums[j].div(counts[j] as f64);
+            let shift = euclidean_distance(&centroids[j], &new_centroid);
+            if shift > max_shift {
+                max_shift = shift;
+            }
+            centroids[j] = new_centroid;
+        }
+    }
+    max_shift
 }
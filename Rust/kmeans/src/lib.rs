@@ -0,0 +1,28 @@
+pub mod csv_io;
+pub mod environment;
+pub mod error;
+pub mod experiment_spec;
+pub mod external_metrics;
+pub mod generators;
+pub mod iteration_plots;
+pub mod kmeans_chunked;
+pub mod kmeans_log;
+pub mod kmeans_nd;
+pub mod kmeans_par;
+pub mod kmeans_run;
+pub mod kmeans_seq;
+pub mod metrics;
+pub mod model;
+pub mod n_init;
+pub mod npy;
+pub mod parallel_fraction;
+#[cfg(feature = "parquet")]
+pub mod parquet_io;
+pub mod point;
+pub mod point_file;
+pub mod regression;
+pub mod scaling;
+pub mod scaling_plots;
+pub mod silhouette;
+pub mod stats;
+pub mod sweep;
@@ -1,55 +1,627 @@
-mod kmeans_par;
-mod kmeans_seq;
-mod point;
-use crate::point::Point;
-use rand::prelude::*;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use kmeans::csv_io::{
+    read_csv, write_labeled_csv, Column, CsvDataset, CsvOptions, Header, MalformedRows,
+};
+use kmeans::environment::Environment;
+use kmeans::error::{Error, Result};
+use kmeans::generators::{make_blobs, DatasetSpec};
+use kmeans::iteration_plots::plot_iterations;
+use kmeans::kmeans_log::kmeans_seq_log;
+use kmeans::model::{KMeansConfig, KMeansModel};
+use kmeans::npy::{read_npy, write_npy, NpyArray};
+use kmeans::parallel_fraction::fit_parallel_fraction;
+use kmeans::point::Point;
+use kmeans::point_file::{convert_csv, MappedPoints};
+use kmeans::scaling::{
+    load_results, outlier_warnings, save_csv, save_json, strong_scaling, weak_scaling, BenchConfig,
+};
+use kmeans::scaling_plots::{plot_scaling, plot_scaling_series, ErrorBars, PlotOptions, Series};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::time::Instant;
 
-fn main() {
-    let n_points = 1000000;
-    let k = 4;
-    let max_iters = 100;
-    let tolerance = 0.001;
-    let mut rng = thread_rng();
-    let points: Vec<Point> = (0..n_points)
-        .map(|_| Point {
-            x: rng.gen_range(0.0..100.0),
-            y: rng.gen_range(0.0..100.0),
+// Counts that must be at least 1, rejected by clap with a readable error.
+fn positive(s: &str) -> std::result::Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[derive(Parser)]
+#[command(
+    name = "kmeans",
+    version,
+    about = "K-Means clustering and scaling experiments"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Fit a model to a data set and save it
+    Fit(FitArgs),
+    /// Assign points to the centroids of a saved model
+    Predict(PredictArgs),
+    /// Run sequential k-means and log every iteration to JSON
+    Log(LogArgs),
+    /// Render one image per iteration of a JSON log
+    Visualize(VisualizeArgs),
+    /// Run a scaling experiment
+    Bench {
+        #[command(subcommand)]
+        kind: BenchKind,
+    },
+    /// Convert a CSV file to a memory-mappable point file
+    Convert(ConvertArgs),
+    /// Plot one or more scaling result files on a shared chart
+    Plot(PlotArgs),
+}
+
+#[derive(Args)]
+struct CsvArgs {
+    /// Field delimiter of CSV input
+    #[arg(long, default_value_t = ',')]
+    delimiter: char,
+    /// Column holding x, by name or zero-based index
+    #[arg(long, default_value = "0")]
+    x_column: String,
+    /// Column holding y, by name or zero-based index
+    #[arg(long, default_value = "1")]
+    y_column: String,
+    /// The first row is a header (detected automatically when omitted)
+    #[arg(long, conflicts_with = "no_header")]
+    header: bool,
+    /// The first row is data
+    #[arg(long)]
+    no_header: bool,
+    /// Skip rows that cannot be parsed instead of failing
+    #[arg(long)]
+    skip_malformed: bool,
+}
+
+#[derive(Args)]
+struct FitArgs {
+    /// Input data (.csv, .npy, .kmp point file or .parquet)
+    input: PathBuf,
+    /// Where to save the model (.json, anything else is binary)
+    #[arg(short, long, default_value = "model.json")]
+    model: PathBuf,
+    /// Where to write the training labels (.csv, .npy or .parquet)
+    #[arg(short, long)]
+    labels: Option<PathBuf>,
+    #[arg(short, default_value_t = 8, value_parser = positive)]
+    k: usize,
+    #[arg(long, default_value_t = 100)]
+    max_iters: usize,
+    #[arg(long, default_value_t = 1e-3)]
+    tolerance: f64,
+    #[arg(long, default_value_t = 1, value_parser = positive)]
+    n_init: usize,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    #[command(flatten)]
+    csv: CsvArgs,
+    /// Columns to cluster in Parquet input
+    #[arg(long, num_args = 2, value_names = ["X", "Y"])]
+    columns: Option<Vec<String>>,
+}
+
+#[derive(Args)]
+struct PredictArgs {
+    /// Saved model
+    model: PathBuf,
+    /// Input data (.csv, .npy, .kmp point file or .parquet)
+    input: PathBuf,
+    /// Where to write the labels (.csv, .npy or .parquet)
+    output: PathBuf,
+    #[command(flatten)]
+    csv: CsvArgs,
+    /// Columns to cluster in Parquet input
+    #[arg(long, num_args = 2, value_names = ["X", "Y"])]
+    columns: Option<Vec<String>>,
+}
+
+#[derive(Args)]
+struct LogArgs {
+    /// JSON log to write
+    output: PathBuf,
+    /// Input data; synthetic blobs are generated when omitted
+    #[arg(long)]
+    input: Option<PathBuf>,
+    #[arg(long, default_value_t = 1000)]
+    n_points: usize,
+    #[arg(short, default_value_t = 3, value_parser = positive)]
+    k: usize,
+    #[arg(long, default_value_t = 20)]
+    max_iters: usize,
+    #[arg(long, default_value_t = 0.1)]
+    tolerance: f64,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    #[command(flatten)]
+    csv: CsvArgs,
+}
+
+#[derive(Args)]
+struct VisualizeArgs {
+    /// JSON log written by `kmeans log`
+    log: PathBuf,
+    /// Directory for the images
+    out_dir: PathBuf,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    #[arg(short, default_value_t = 4, value_parser = positive)]
+    k: usize,
+    #[arg(long, default_value_t = 100)]
+    max_iters: usize,
+    #[arg(long, default_value_t = 1e-3)]
+    tolerance: f64,
+    #[arg(long, default_value_t = 8, value_parser = positive)]
+    max_threads: usize,
+    /// Timed repetitions per thread count
+    #[arg(long, default_value_t = 5, value_parser = positive)]
+    runs: usize,
+    /// Untimed warm-up fits per thread count
+    #[arg(long, default_value_t = 1)]
+    warmup: usize,
+    /// Always run max-iters iterations, ignoring the tolerance
+    #[arg(long)]
+    fixed_iterations: bool,
+    /// Bootstrap resamples for the median confidence interval
+    #[arg(long, default_value_t = 1000)]
+    bootstrap: usize,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Parallel fraction for the Amdahl/Gustafson curve
+    #[arg(short, default_value_t = 0.9)]
+    p: f64,
+    /// Results table; the environment is written beside it as `<name>.environment.json`
+    #[arg(long)]
+    csv: PathBuf,
+    /// Results with raw samples as JSON
+    #[arg(long)]
+    json: Option<PathBuf>,
+    /// Speedup plot
+    #[arg(long)]
+    plot: PathBuf,
+}
+
+impl BenchArgs {
+    fn bench_config(&self) -> BenchConfig {
+        BenchConfig {
+            warmup: self.warmup,
+            repetitions: self.runs,
+            fixed_iterations: self.fixed_iterations,
+            bootstrap_resamples: self.bootstrap,
+            seed: self.seed,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum BenchKind {
+    /// Fixed problem size, growing thread count
+    Strong {
+        #[arg(long, default_value_t = 100000, value_parser = positive)]
+        n_points: usize,
+        #[command(flatten)]
+        args: BenchArgs,
+    },
+    /// Problem size growing with the thread count
+    Weak {
+        #[arg(long, default_value_t = 200000, value_parser = positive)]
+        base_points: usize,
+        #[command(flatten)]
+        args: BenchArgs,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ErrorBarsArg {
+    Std,
+    Ci,
+    None,
+}
+
+#[derive(Args)]
+struct PlotArgs {
+    /// Result files (.csv or .json), each optionally written as PATH=LABEL
+    #[arg(required = true)]
+    results: Vec<String>,
+    /// Output image; .svg for vector output, otherwise a bitmap
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Law curve to draw: amdahl, gustafson or none
+    #[arg(long, default_value = "amdahl")]
+    law: String,
+    /// Assumed parallel fraction for the law curve
+    #[arg(short, default_value_t = 0.9)]
+    p: f64,
+    /// Fit the parallel fraction of every file and draw its curve
+    #[arg(long)]
+    fit: bool,
+    #[arg(long, value_enum, default_value_t = ErrorBarsArg::Std)]
+    error_bars: ErrorBarsArg,
+    /// Base-2 logarithmic axes
+    #[arg(long)]
+    log2: bool,
+}
+
+#[derive(Args)]
+struct ConvertArgs {
+    input: PathBuf,
+    output: PathBuf,
+    #[command(flatten)]
+    csv: CsvArgs,
+}
+
+fn column(spec: &str) -> Column {
+    match spec.parse::<usize>() {
+        Ok(index) => Column::Index(index),
+        Err(_) => Column::Name(spec.to_string()),
+    }
+}
+
+impl CsvArgs {
+    fn options(&self) -> Result<CsvOptions> {
+        if !self.delimiter.is_ascii() {
+            return Err(Error::InvalidFormat(format!(
+                "delimiter '{}' is not a single byte",
+                self.delimiter
+            )));
+        }
+        Ok(CsvOptions {
+            delimiter: self.delimiter as u8,
+            header: match (self.header, self.no_header) {
+                (true, _) => Header::Present,
+                (_, true) => Header::Absent,
+                _ => Header::Auto,
+            },
+            x: column(&self.x_column),
+            y: column(&self.y_column),
+            malformed: if self.skip_malformed {
+                MalformedRows::Skip
+            } else {
+                MalformedRows::Fail
+            },
         })
-        .collect();
+    }
+}
 
-    let initial_centroids: Vec<Point> = points.choose_multiple(&mut rng, k).cloned().collect();
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
 
-    let start_seq = Instant::now();
-    let (seq_centroids, seq_assignments) = kmeans_seq::kmeans_seq(
-        &points,
-        k,
-        max_iters,
-        tolerance,
-        Some(initial_centroids.clone()),
+/// Points loaded from any supported input, plus what is needed to write the
+/// input rows back out with labels.
+enum Input {
+    Csv(CsvDataset),
+    Points(Vec<Point>),
+    #[cfg(feature = "parquet")]
+    Parquet(Vec<arrow::record_batch::RecordBatch>, Vec<Point>),
+}
+
+impl Input {
+    fn points(&self) -> &[Point] {
+        match self {
+            Input::Csv(data) => &data.points,
+            Input::Points(points) => points,
+            #[cfg(feature = "parquet")]
+            Input::Parquet(_, points) => points,
+        }
+    }
+}
+
+fn require(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Err(Error::InvalidFormat(format!(
+            "{} does not exist",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "parquet"), allow(unused_variables))]
+fn load(path: &Path, csv: &CsvArgs, columns: Option<&[String]>) -> Result<Input> {
+    require(path)?;
+    match extension(path).as_str() {
+        "npy" => Ok(Input::Points(read_npy(path)?.to_points()?)),
+        "kmp" => Ok(Input::Points(MappedPoints::open(path)?.to_points()?)),
+        #[cfg(feature = "parquet")]
+        "parquet" => {
+            let (x, y) = match columns {
+                Some([x, y]) => (x.as_str(), y.as_str()),
+                _ => {
+                    return Err(Error::InvalidFormat(
+                        "Parquet input needs --columns X Y".to_string(),
+                    ))
+                }
+            };
+            let batches = kmeans::parquet_io::read_parquet(path)?;
+            let points = kmeans::parquet_io::points_from_batches(
+                &batches,
+                x,
+                y,
+                kmeans::parquet_io::NullHandling::Reject,
+            )?;
+            Ok(Input::Parquet(batches, points))
+        }
+        _ => {
+            let data = read_csv(path, &csv.options()?)?;
+            if !data.skipped_lines.is_empty() {
+                eprintln!("Skipped {} malformed rows", data.skipped_lines.len());
+            }
+            Ok(Input::Csv(data))
+        }
+    }
+}
+
+fn write_labels(path: &Path, input: &Input, labels: &[usize], csv: &CsvArgs) -> Result<()> {
+    match (extension(path).as_str(), input) {
+        ("npy", _) => write_npy(path, &NpyArray::from_labels(labels)),
+        #[cfg(feature = "parquet")]
+        ("parquet", Input::Parquet(batches, _)) => {
+            kmeans::parquet_io::write_parquet_with_labels(path, batches, labels, "cluster")
+        }
+        ("csv", Input::Csv(data)) => {
+            write_labeled_csv(path, data, labels, csv.options()?.delimiter)
+        }
+        ("csv", _) => {
+            let data = CsvDataset {
+                headers: Some(["x", "y"].iter().collect()),
+                records: input
+                    .points()
+                    .iter()
+                    .map(|p| [p.x.to_string(), p.y.to_string()].iter().collect())
+                    .collect(),
+                points: Vec::new(),
+                skipped_lines: Vec::new(),
+            };
+            write_labeled_csv(path, &data, labels, b',')
+        }
+        (ext, _) => Err(Error::InvalidFormat(format!(
+            "cannot write labels as '.{}' for this input",
+            ext
+        ))),
+    }
+}
+
+fn save_model(model: &KMeansModel, path: &Path) -> Result<()> {
+    if extension(path) == "json" {
+        model.save_json(path)
+    } else {
+        model.save_binary(path)
+    }
+}
+
+fn load_model(path: &Path) -> Result<KMeansModel> {
+    require(path)?;
+    if extension(path) == "json" {
+        KMeansModel::load_json(path)
+    } else {
+        KMeansModel::load_binary(path)
+    }
+}
+
+fn fit(args: FitArgs) -> Result<()> {
+    let input = load(&args.input, &args.csv, args.columns.as_deref())?;
+    check_k(args.k, input.points().len())?;
+    let config = KMeansConfig {
+        k: args.k,
+        max_iters: args.max_iters,
+        tolerance: args.tolerance,
+        n_init: args.n_init,
+        seed: args.seed,
+    };
+    let start = Instant::now();
+    let (model, labels) = KMeansModel::fit(input.points(), config);
+    println!(
+        "Fitted {} points in {:.2?}",
+        input.points().len(),
+        start.elapsed()
     );
-    let duration_seq = start_seq.elapsed();
-    println!("Sequential K-Means: Final centroids:");
-    for (i, c) in seq_centroids.iter().enumerate() {
+    for (i, c) in model.centroids.iter().enumerate() {
         println!("Cluster {}: ({:.2}, {:.2})", i, c.x, c.y);
     }
-    println!("Time elapsed: {:.2?}", duration_seq);
+    save_model(&model, &args.model)?;
+    println!("Model saved to {}", args.model.display());
+    if let Some(path) = &args.labels {
+        write_labels(path, &input, &labels, &args.csv)?;
+        println!("Labels written to {}", path.display());
+    }
+    Ok(())
+}
+
+fn predict(args: PredictArgs) -> Result<()> {
+    let model = load_model(&args.model)?;
+    let input = load(&args.input, &args.csv, args.columns.as_deref())?;
+    let labels = model.predict_par(input.points());
+    write_labels(&args.output, &input, &labels, &args.csv)?;
+    println!(
+        "Assigned {} points to {} clusters, labels written to {}",
+        labels.len(),
+        model.k(),
+        args.output.display()
+    );
+    Ok(())
+}
 
-    let start_par = Instant::now();
-    let (par_centroids, par_assignments) = kmeans_par::kmeans_par(
+fn log(args: LogArgs) -> Result<()> {
+    let points = match &args.input {
+        Some(path) => load(path, &args.csv, None)?.points().to_vec(),
+        None => make_blobs(args.n_points, args.k, 5.0, (10.0, 90.0), args.seed).points,
+    };
+    check_k(args.k, points.len())?;
+    let initial_centroids = kmeans::n_init::random_centroids(&points, args.k, args.seed);
+    println!("Running sequential KMeans with logging...");
+    kmeans_seq_log(
         &points,
-        k,
-        max_iters,
-        tolerance,
-        Some(initial_centroids.clone()),
+        args.k,
+        args.max_iters,
+        args.tolerance,
+        Some(initial_centroids),
+        &args.output,
+    )?;
+    println!("Log file created at: {}", args.output.display());
+    Ok(())
+}
+
+fn check_k(k: usize, n_points: usize) -> Result<()> {
+    if k > n_points {
+        return Err(Error::InvalidFormat(format!(
+            "k = {} is larger than the {} points",
+            k, n_points
+        )));
+    }
+    Ok(())
+}
+
+fn bench(kind: BenchKind) -> Result<()> {
+    let (results, args, law) = match kind {
+        BenchKind::Strong { n_points, args } => {
+            check_k(args.k, n_points)?;
+            println!("Running strong scaling experiment...");
+            let threads: Vec<usize> = (1..=args.max_threads).collect();
+            let results = strong_scaling(
+                &DatasetSpec::default(),
+                n_points,
+                args.k,
+                args.max_iters,
+                args.tolerance,
+                &threads,
+                &args.bench_config(),
+            )?;
+            (results, args, "amdahl")
+        }
+        BenchKind::Weak { base_points, args } => {
+            check_k(args.k, base_points)?;
+            println!("Running weak scaling experiment...");
+            let threads: Vec<usize> = (1..=args.max_threads).collect();
+            let results = weak_scaling(
+                &DatasetSpec::default(),
+                base_points,
+                args.k,
+                args.max_iters,
+                args.tolerance,
+                &threads,
+                &args.bench_config(),
+            )?;
+            (results, args, "gustafson")
+        }
+    };
+    for warning in outlier_warnings(&results) {
+        eprintln!("warning: {}", warning);
+    }
+    save_csv(&args.csv, &results)?;
+    Environment::capture().save(Environment::sidecar_path(&args.csv))?;
+    if let Some(json) = &args.json {
+        save_json(json, &results)?;
+    }
+    let fit = fit_parallel_fraction(&results, law);
+    if let Some(fit) = &fit {
+        print!("{}", fit);
+    }
+    plot_scaling(
+        &args.plot,
+        &results,
+        law,
+        args.p,
+        fit.and_then(|f| f.valid_p()),
+    )?;
+    println!(
+        "Results written to {} and {}",
+        args.csv.display(),
+        args.plot.display()
     );
-    let duration_par = start_par.elapsed();
-    println!("\nParallel K-Means: Final centroids:");
-    for (i, c) in par_centroids.iter().enumerate() {
-        println!("Cluster {}: ({:.2}, {:.2})", i, c.x, c.y);
+    Ok(())
+}
+
+fn plot(args: PlotArgs) -> Result<()> {
+    let mut loaded = Vec::new();
+    for spec in &args.results {
+        let (path, label) = match spec.rsplit_once('=') {
+            Some((path, label)) => (PathBuf::from(path), label.to_string()),
+            None => {
+                let path = PathBuf::from(spec);
+                let label = path
+                    .file_stem()
+                    .map_or_else(|| spec.clone(), |s| s.to_string_lossy().into_owned());
+                (path, label)
+            }
+        };
+        require(&path)?;
+        let results = load_results(&path)?;
+        let fitted_p = if args.fit {
+            let fit = fit_parallel_fraction(&results, &args.law);
+            if let Some(f) = fit.as_ref().filter(|f| !f.in_range) {
+                eprintln!(
+                    "warning: fitted p = {:.4} for {} is outside [0, 1]; no fitted curve drawn",
+                    f.p, label
+                );
+            }
+            fit.and_then(|f| f.valid_p())
+        } else {
+            None
+        };
+        loaded.push((label, results, fitted_p));
+    }
+    let series: Vec<Series> = loaded
+        .iter()
+        .map(|(label, results, fitted_p)| Series {
+            label: label.clone(),
+            results,
+            fitted_p: *fitted_p,
+        })
+        .collect();
+    let opts = PlotOptions {
+        error_bars: match args.error_bars {
+            ErrorBarsArg::Std => ErrorBars::Std,
+            ErrorBarsArg::Ci => ErrorBars::Ci,
+            ErrorBarsArg::None => ErrorBars::None,
+        },
+        log2: args.log2,
+    };
+    plot_scaling_series(&args.output, &series, &args.law, args.p, &opts)?;
+    println!("Plot written to {}", args.output.display());
+    Ok(())
+}
+
+fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Command::Fit(args) => fit(args),
+        Command::Predict(args) => predict(args),
+        Command::Log(args) => log(args),
+        Command::Visualize(args) => {
+            let n = plot_iterations(&args.log, &args.out_dir)?;
+            println!("{} images written to {}", n, args.out_dir.display());
+            Ok(())
+        }
+        Command::Bench { kind } => bench(kind),
+        Command::Convert(args) => {
+            let n = convert_csv(&args.input, &args.output, &args.csv.options()?)?;
+            println!("{} points written to {}", n, args.output.display());
+            Ok(())
+        }
+        Command::Plot(args) => plot(args),
     }
-    println!("Time elapsed: {:.2?}", duration_par);
+}
 
-    println!("First Seq assignment {}", seq_assignments[0]);
-    println!("First Par assignment {}", par_assignments[0]);
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,198 @@
+use crate::point::{euclidean_distance, Point};
+use rayon::prelude::*;
+
+/// Sum of squared distances of every point to the centroid it is assigned to.
+pub fn inertia(points: &[Point], centroids: &[Point], assignments: &[usize]) -> f64 {
+    points
+        .iter()
+        .zip(assignments.iter())
+        .map(|(point, &cluster)| euclidean_distance(point, &centroids[cluster]).powi(2))
+        .sum()
+}
+
+pub fn inertia_par(points: &[Point], centroids: &[Point], assignments: &[usize]) -> f64 {
+    points
+        .par_iter()
+        .zip(assignments.par_iter())
+        .map(|(point, &cluster)| euclidean_distance(point, &centroids[cluster]).powi(2))
+        .sum()
+}
+
+// Per cluster: sum of distances to the centroid and number of points.
+fn scatter(points: &[Point], centroids: &[Point], assignments: &[usize]) -> (Vec<f64>, Vec<usize>) {
+    let k = centroids.len();
+    let mut dists = vec![0.0; k];
+    let mut counts = vec![0usize; k];
+    for (point, &cluster) in points.iter().zip(assignments.iter()) {
+        dists[cluster] += euclidean_distance(point, &centroids[cluster]);
+        counts[cluster] += 1;
+    }
+    (dists, counts)
+}
+
+fn scatter_par(
+    points: &[Point],
+    centroids: &[Point],
+    assignments: &[usize],
+) -> (Vec<f64>, Vec<usize>) {
+    let k = centroids.len();
+    points
+        .par_iter()
+        .zip(assignments.par_iter())
+        .fold(
+            || (vec![0.0; k], vec![0usize; k]),
+            |mut acc, (point, &cluster)| {
+                acc.0[cluster] += euclidean_distance(point, &centroids[cluster]);
+                acc.1[cluster] += 1;
+                acc
+            },
+        )
+        .reduce(
+            || (vec![0.0; k], vec![0usize; k]),
+            |(mut dists1, mut counts1), (dists2, counts2)| {
+                for j in 0..k {
+                    dists1[j] += dists2[j];
+                    counts1[j] += counts2[j];
+                }
+                (dists1, counts1)
+            },
+        )
+}
+
+fn davies_bouldin_from(centroids: &[Point], dists: &[f64], counts: &[usize]) -> f64 {
+    let clusters: Vec<usize> = (0..centroids.len()).filter(|&j| counts[j] > 0).collect();
+    if clusters.len() < 2 {
+        return 0.0;
+    }
+    let spread: Vec<f64> = (0..centroids.len())
+        .map(|j| {
+            if counts[j] > 0 {
+                dists[j] / counts[j] as f64
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let total: f64 = clusters
+        .iter()
+        .map(|&i| {
+            clusters
+                .iter()
+                .filter(|&&j| j != i)
+                .map(|&j| {
+                    let separation = euclidean_distance(&centroids[i], &centroids[j]);
+                    if separation > 0.0 {
+                        (spread[i] + spread[j]) / separation
+                    } else {
+                        f64::INFINITY
+                    }
+                })
+                .fold(0.0, f64::max)
+        })
+        .sum();
+    total / clusters.len() as f64
+}
+
+/// Davies–Bouldin index; lower is better.
+pub fn davies_bouldin(points: &[Point], centroids: &[Point], assignments: &[usize]) -> f64 {
+    let (dists, counts) = scatter(points, centroids, assignments);
+    davies_bouldin_from(centroids, &dists, &counts)
+}
+
+pub fn davies_bouldin_par(points: &[Point], centroids: &[Point], assignments: &[usize]) -> f64 {
+    let (dists, counts) = scatter_par(points, centroids, assignments);
+    davies_bouldin_from(centroids, &dists, &counts)
+}
+
+fn calinski_harabasz_from(
+    n: usize,
+    mean: &Point,
+    centroids: &[Point],
+    counts: &[usize],
+    within: f64,
+) -> f64 {
+    let k = counts.iter().filter(|&&c| c > 0).count();
+    if k < 2 || n <= k || within == 0.0 {
+        return 0.0;
+    }
+    let between: f64 = centroids
+        .iter()
+        .zip(counts.iter())
+        .map(|(c, &count)| count as f64 * euclidean_distance(c, mean).powi(2))
+        .sum();
+    (between / (k - 1) as f64) / (within / (n - k) as f64)
+}
+
+/// Calinski–Harabasz (variance ratio) index; higher is better.
+pub fn calinski_harabasz(points: &[Point], centroids: &[Point], assignments: &[usize]) -> f64 {
+    if points.is_empty() {
+        return 0.0;
+    }
+    let mean = points
+        .iter()
+        .fold(Point::zero(), |acc, p| acc.add(p))
+        .div(points.len() as f64);
+    let (_, counts) = scatter(points, centroids, assignments);
+    let within = inertia(points, centroids, assignments);
+    calinski_harabasz_from(points.len(), &mean, centroids, &counts, within)
+}
+
+pub fn calinski_harabasz_par(points: &[Point], centroids: &[Point], assignments: &[usize]) -> f64 {
+    if points.is_empty() {
+        return 0.0;
+    }
+    let mean = points
+        .par_iter()
+        .fold(Point::zero, |acc, p| acc.add(p))
+        .reduce(Point::zero, |a, b| a.add(&b))
+        .div(points.len() as f64);
+    let (_, counts) = scatter_par(points, centroids, assignments);
+    let within = inertia_par(points, centroids, assignments);
+    calinski_harabasz_from(points.len(), &mean, centroids, &counts, within)
+}
+
+// Smallest distance between points of different clusters and largest distance
+// between points of the same cluster, over all pairs (i, j) with j > i.
+fn dunn_pairs(points: &[Point], assignments: &[usize], i: usize) -> (f64, f64) {
+    let mut min_between = f64::MAX;
+    let mut max_within = 0.0f64;
+    for j in (i + 1)..points.len() {
+        let dist = euclidean_distance(&points[i], &points[j]);
+        if assignments[i] == assignments[j] {
+            max_within = max_within.max(dist);
+        } else {
+            min_between = min_between.min(dist);
+        }
+    }
+    (min_between, max_within)
+}
+
+fn dunn_from((min_between, max_within): (f64, f64)) -> f64 {
+    if min_between == f64::MAX || max_within == 0.0 {
+        return 0.0;
+    }
+    min_between / max_within
+}
+
+/// Dunn index over all point pairs (O(n^2)); higher is better.
+pub fn dunn(points: &[Point], assignments: &[usize]) -> f64 {
+    dunn_from(
+        (0..points.len())
+            .map(|i| dunn_pairs(points, assignments, i))
+            .fold((f64::MAX, 0.0), |(b1, w1), (b2, w2)| {
+                (b1.min(b2), w1.max(w2))
+            }),
+    )
+}
+
+pub fn dunn_par(points: &[Point], assignments: &[usize]) -> f64 {
+    dunn_from(
+        (0..points.len())
+            .into_par_iter()
+            .map(|i| dunn_pairs(points, assignments, i))
+            .reduce(
+                || (f64::MAX, 0.0),
+                |(b1, w1), (b2, w2)| (b1.min(b2), w1.max(w2)),
+            ),
+    )
+}
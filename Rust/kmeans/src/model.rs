@@ -0,0 +1,269 @@
+use crate::error::{Error, Result};
+use crate::n_init::kmeans_n_init;
+use crate::point::{euclidean_distance, nearest_centroid, Point};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Bumped whenever the saved layout of `KMeansModel` changes.
+pub const MODEL_FORMAT_VERSION: u32 = 1;
+const BINARY_MAGIC: &[u8; 4] = b"KMNS";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KMeansConfig {
+    pub k: usize,
+    pub max_iters: usize,
+    pub tolerance: f64,
+    pub n_init: usize,
+    pub seed: u64,
+}
+
+impl Default for KMeansConfig {
+    fn default() -> Self {
+        KMeansConfig {
+            k: 8,
+            max_iters: 100,
+            tolerance: 1e-3,
+            n_init: 1,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingInfo {
+    pub n_points: usize,
+    pub inertia: f64,
+    pub run_inertias: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KMeansModel {
+    pub config: KMeansConfig,
+    pub centroids: Vec<Point>,
+    pub training: Option<TrainingInfo>,
+    pub crate_version: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ModelFile {
+    format_version: u32,
+    model: KMeansModel,
+}
+
+fn check_version(found: u32) -> Result<()> {
+    if found != MODEL_FORMAT_VERSION {
+        return Err(Error::IncompatibleVersion {
+            found,
+            supported: MODEL_FORMAT_VERSION,
+        });
+    }
+    Ok(())
+}
+
+impl KMeansModel {
+    /// Fits a model and also returns the training assignments.
+    pub fn fit(points: &[Point], config: KMeansConfig) -> (Self, Vec<usize>) {
+        let result = kmeans_n_init(
+            points,
+            config.k,
+            config.max_iters,
+            config.tolerance,
+            config.n_init,
+            config.seed,
+        );
+        let model = KMeansModel {
+            config,
+            centroids: result.centroids,
+            training: Some(TrainingInfo {
+                n_points: points.len(),
+                inertia: result.inertia,
+                run_inertias: result.inertias,
+            }),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        };
+        (model, result.assignments)
+    }
+
+    pub fn from_centroids(centroids: Vec<Point>) -> Self {
+        KMeansModel {
+            config: KMeansConfig {
+                k: centroids.len(),
+                ..KMeansConfig::default()
+            },
+            centroids,
+            training: None,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    pub fn k(&self) -> usize {
+        self.centroids.len()
+    }
+
+    pub fn predict(&self, points: &[Point]) -> Vec<usize> {
+        points
+            .iter()
+            .map(|p| nearest_centroid(p, &self.centroids))
+            .collect()
+    }
+
+    pub fn predict_par(&self, points: &[Point]) -> Vec<usize> {
+        points
+            .par_iter()
+            .map(|p| nearest_centroid(p, &self.centroids))
+            .collect()
+    }
+
+    /// Distance of every point to every centroid, one row of length k per point.
+    pub fn transform(&self, points: &[Point]) -> Vec<Vec<f64>> {
+        points.iter().map(|p| self.distances(p)).collect()
+    }
+
+    pub fn transform_par(&self, points: &[Point]) -> Vec<Vec<f64>> {
+        points.par_iter().map(|p| self.distances(p)).collect()
+    }
+
+    /// Negative inertia of `points` under the learned centroids (higher is better).
+    pub fn score(&self, points: &[Point]) -> f64 {
+        -points
+            .par_iter()
+            .map(|p| {
+                let nearest = &self.centroids[nearest_centroid(p, &self.centroids)];
+                euclidean_distance(p, nearest).powi(2)
+            })
+            .sum::<f64>()
+    }
+
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = ModelFile {
+            format_version: MODEL_FORMAT_VERSION,
+            model: self.clone(),
+        };
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, &file)?;
+        Ok(())
+    }
+
+    pub fn load_json(path: impl AsRef<Path>) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        let found = value["format_version"]
+            .as_u64()
+            .ok_or_else(|| Error::InvalidFormat("missing format_version".to_string()))?;
+        check_version(found as u32)?;
+        let file: ModelFile = serde_json::from_value(value)?;
+        Ok(file.model)
+    }
+
+    /// Compact binary form: the `KMNS` magic, the format version as a
+    /// little-endian u32, then the bincode-encoded model.
+    pub fn save_binary(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(BINARY_MAGIC)?;
+        writer.write_all(&MODEL_FORMAT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load_binary(path: impl AsRef<Path>) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != BINARY_MAGIC {
+            return Err(Error::InvalidFormat("not a k-means model file".to_string()));
+        }
+        check_version(u32::from_le_bytes(header[4..].try_into().unwrap()))?;
+        Ok(bincode::deserialize_from(reader)?)
+    }
+
+    fn distances(&self, point: &Point) -> Vec<f64> {
+        self.centroids
+            .iter()
+            .map(|c| euclidean_distance(point, c))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("kmeans-{}-{}", std::process::id(), name))
+    }
+
+    fn model() -> KMeansModel {
+        let points = vec![
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 0.5, y: 0.0 },
+            Point { x: 10.0, y: 10.0 },
+            Point { x: 10.5, y: 10.0 },
+        ];
+        let config = KMeansConfig {
+            k: 2,
+            n_init: 2,
+            seed: 7,
+            ..KMeansConfig::default()
+        };
+        KMeansModel::fit(&points, config).0
+    }
+
+    fn assert_same(a: &KMeansModel, b: &KMeansModel) {
+        assert_eq!(a.config.k, b.config.k);
+        assert_eq!(a.config.seed, b.config.seed);
+        assert_eq!(a.crate_version, b.crate_version);
+        assert_eq!(a.k(), b.k());
+        for (p, q) in a.centroids.iter().zip(&b.centroids) {
+            assert_eq!((p.x, p.y), (q.x, q.y));
+        }
+        let (ta, tb) = (a.training.as_ref().unwrap(), b.training.as_ref().unwrap());
+        assert_eq!(ta.n_points, tb.n_points);
+        assert_eq!(ta.inertia, tb.inertia);
+        assert_eq!(ta.run_inertias, tb.run_inertias);
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let path = temp_path("model.json");
+        let model = model();
+        model.save_json(&path).unwrap();
+        let loaded = KMeansModel::load_json(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_same(&model, &loaded);
+    }
+
+    #[test]
+    fn binary_round_trips() {
+        let path = temp_path("model.bin");
+        let model = model();
+        model.save_binary(&path).unwrap();
+        let loaded = KMeansModel::load_binary(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_same(&model, &loaded);
+    }
+
+    #[test]
+    fn rejects_other_format_versions() {
+        let path = temp_path("model-v2.json");
+        let mut value = serde_json::to_value(ModelFile {
+            format_version: MODEL_FORMAT_VERSION,
+            model: model(),
+        })
+        .unwrap();
+        value["format_version"] = 2.into();
+        std::fs::write(&path, value.to_string()).unwrap();
+        let result = KMeansModel::load_json(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            result,
+            Err(Error::IncompatibleVersion {
+                found: 2,
+                supported: MODEL_FORMAT_VERSION
+            })
+        ));
+    }
+}
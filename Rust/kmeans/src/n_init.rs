@@ -0,0 +1,94 @@
+use crate::kmeans_par::kmeans_par;
+use crate::kmeans_seq::kmeans_seq;
+use crate::metrics::{inertia, inertia_par};
+use crate::point::Point;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rayon::prelude::*;
+
+/// Above this many points the restarts run one after another with
+/// `kmeans_par`; below it they run concurrently with `kmeans_seq`.
+pub const PARALLEL_RESTARTS_MAX_POINTS: usize = 100_000;
+
+#[derive(Debug, Clone)]
+pub struct NInitResult {
+    pub centroids: Vec<Point>,
+    pub assignments: Vec<usize>,
+    pub inertia: f64,
+    pub best_run: usize,
+    pub inertias: Vec<f64>,
+}
+
+pub fn random_centroids(points: &[Point], k: usize, seed: u64) -> Vec<Point> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    points.choose_multiple(&mut rng, k).cloned().collect()
+}
+
+/// Runs `n_init` initializations, run `r` seeded with `seed + r` (wrapping),
+/// and keeps the one with the lowest inertia; NaN inertias never win.
+pub fn kmeans_n_init(
+    points: &[Point],
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    n_init: usize,
+    seed: u64,
+) -> NInitResult {
+    assert!(n_init > 0, "n_init must be at least 1");
+    // NaN sorts last whatever its sign bit.
+    let key = |x: f64| if x.is_nan() { f64::INFINITY } else { x };
+    if points.len() <= PARALLEL_RESTARTS_MAX_POINTS {
+        let runs: Vec<(Vec<Point>, Vec<usize>, f64)> = (0..n_init)
+            .into_par_iter()
+            .map(|run| {
+                let initial = random_centroids(points, k, seed.wrapping_add(run as u64));
+                let (centroids, assignments) =
+                    kmeans_seq(points, k, max_iters, tolerance, Some(initial));
+                let inertia = inertia(points, &centroids, &assignments);
+                (centroids, assignments, inertia)
+            })
+            .collect();
+        let inertias: Vec<f64> = runs.iter().map(|r| r.2).collect();
+        let best_run = inertias
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| key(**a).total_cmp(&key(**b)))
+            .map(|(i, _)| i)
+            .unwrap();
+        let (centroids, assignments, inertia) = runs.into_iter().nth(best_run).unwrap();
+        return NInitResult {
+            centroids,
+            assignments,
+            inertia,
+            best_run,
+            inertias,
+        };
+    }
+
+    // Large inputs keep only the best run so far rather than n_init
+    // assignment vectors.
+    let mut best: Option<NInitResult> = None;
+    let mut inertias = Vec::with_capacity(n_init);
+    for run in 0..n_init {
+        let initial = random_centroids(points, k, seed.wrapping_add(run as u64));
+        let (centroids, assignments) = kmeans_par(points, k, max_iters, tolerance, Some(initial));
+        let inertia = inertia_par(points, &centroids, &assignments);
+        inertias.push(inertia);
+        if best
+            .as_ref()
+            .is_none_or(|b| key(inertia).total_cmp(&key(b.inertia)).is_lt())
+        {
+            best = Some(NInitResult {
+                centroids,
+                assignments,
+                inertia,
+                best_run: run,
+                inertias: Vec::new(),
+            });
+        }
+    }
+    let mut best = best.unwrap();
+    best.inertias = inertias;
+    best
+}
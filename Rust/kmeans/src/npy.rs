@@ -0,0 +1,403 @@
+use crate::error::{Error, Result};
+use crate::point::Point;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dtype {
+    F32,
+    F64,
+    I64,
+}
+
+impl Dtype {
+    fn size(self) -> usize {
+        match self {
+            Dtype::F32 => 4,
+            Dtype::F64 | Dtype::I64 => 8,
+        }
+    }
+
+    fn descr(self) -> &'static str {
+        match self {
+            Dtype::F32 => "<f4",
+            Dtype::F64 => "<f8",
+            Dtype::I64 => "<i8",
+        }
+    }
+}
+
+/// An n-dimensional array as stored in a `.npy` file. `data` is always kept
+/// in C (row-major) order; `dtype` is the element type used on disk.
+#[derive(Debug, Clone)]
+pub struct NpyArray {
+    pub shape: Vec<usize>,
+    pub dtype: Dtype,
+    pub data: Vec<f64>,
+}
+
+impl NpyArray {
+    pub fn from_points(points: &[Point]) -> Self {
+        NpyArray {
+            shape: vec![points.len(), 2],
+            dtype: Dtype::F64,
+            data: points.iter().flat_map(|p| [p.x, p.y]).collect(),
+        }
+    }
+
+    pub fn from_labels(labels: &[usize]) -> Self {
+        NpyArray {
+            shape: vec![labels.len()],
+            dtype: Dtype::I64,
+            data: labels.iter().map(|&l| l as f64).collect(),
+        }
+    }
+
+    /// Interprets an `(n, 2)` array as points.
+    pub fn to_points(&self) -> Result<Vec<Point>> {
+        if self.shape.len() != 2 || self.shape[1] != 2 {
+            return Err(Error::InvalidFormat(format!(
+                "expected an array of shape (n, 2), found {:?}",
+                self.shape
+            )));
+        }
+        Ok(self
+            .data
+            .chunks_exact(2)
+            .map(|c| Point { x: c[0], y: c[1] })
+            .collect())
+    }
+
+    pub fn to_labels(&self) -> Result<Vec<usize>> {
+        if self.shape.len() != 1 {
+            return Err(Error::InvalidFormat(format!(
+                "expected a 1-D label array, found shape {:?}",
+                self.shape
+            )));
+        }
+        self.data
+            .iter()
+            .map(|&l| {
+                if l >= 0.0 && l.fract() == 0.0 && l < usize::MAX as f64 {
+                    Ok(l as usize)
+                } else {
+                    Err(Error::InvalidFormat(format!(
+                        "label {} is not a non-negative integer",
+                        l
+                    )))
+                }
+            })
+            .collect()
+    }
+}
+
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str> {
+    let pattern = format!("'{}':", key);
+    let start = header
+        .find(&pattern)
+        .ok_or_else(|| Error::InvalidFormat(format!("npy header has no '{}'", key)))?
+        + pattern.len();
+    Ok(header[start..].trim_start())
+}
+
+fn parse_header(header: &str) -> Result<(Dtype, bool, bool, Vec<usize>)> {
+    let descr = header_value(header, "descr")?;
+    let descr = descr
+        .strip_prefix('\'')
+        .and_then(|d| d.split('\'').next())
+        .ok_or_else(|| Error::InvalidFormat("malformed descr in npy header".to_string()))?;
+    let (big_endian, kind) = match descr.split_at(1) {
+        ("<", kind) | ("|", kind) | ("=", kind) => (false, kind),
+        (">", kind) => (true, kind),
+        _ => (false, descr),
+    };
+    let dtype = match kind {
+        "f4" => Dtype::F32,
+        "f8" => Dtype::F64,
+        "i8" => Dtype::I64,
+        _ => {
+            return Err(Error::InvalidFormat(format!(
+                "unsupported npy dtype '{}'",
+                descr
+            )))
+        }
+    };
+
+    let fortran_order = header_value(header, "fortran_order")?.starts_with("True");
+
+    let shape = header_value(header, "shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|s| s.split(')').next())
+        .ok_or_else(|| Error::InvalidFormat("malformed shape in npy header".to_string()))?;
+    let shape = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<usize>()
+                .map_err(|_| Error::InvalidFormat(format!("bad npy dimension '{}'", s)))
+        })
+        .collect::<Result<Vec<usize>>>()?;
+    Ok((dtype, big_endian, fortran_order, shape))
+}
+
+fn decode(bytes: &[u8], dtype: Dtype, big_endian: bool) -> Vec<f64> {
+    bytes
+        .chunks_exact(dtype.size())
+        .map(|b| match (dtype, big_endian) {
+            (Dtype::F32, false) => f32::from_le_bytes(b.try_into().unwrap()) as f64,
+            (Dtype::F32, true) => f32::from_be_bytes(b.try_into().unwrap()) as f64,
+            (Dtype::F64, false) => f64::from_le_bytes(b.try_into().unwrap()),
+            (Dtype::F64, true) => f64::from_be_bytes(b.try_into().unwrap()),
+            (Dtype::I64, false) => i64::from_le_bytes(b.try_into().unwrap()) as f64,
+            (Dtype::I64, true) => i64::from_be_bytes(b.try_into().unwrap()) as f64,
+        })
+        .collect()
+}
+
+// Reorders column-major data into row-major order.
+fn fortran_to_c(data: &[f64], shape: &[usize]) -> Vec<f64> {
+    let mut out = vec![0.0; data.len()];
+    let mut index = vec![0usize; shape.len()];
+    for value in out.iter_mut() {
+        let mut offset = 0;
+        let mut stride = 1;
+        for (i, &dim) in index.iter().zip(shape.iter()) {
+            offset += i * stride;
+            stride *= dim;
+        }
+        *value = data[offset];
+        for axis in (0..shape.len()).rev() {
+            index[axis] += 1;
+            if index[axis] < shape[axis] {
+                break;
+            }
+            index[axis] = 0;
+        }
+    }
+    out
+}
+
+pub fn read_npy_from(mut reader: impl Read) -> Result<NpyArray> {
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble)?;
+    if &preamble[..6] != MAGIC {
+        return Err(Error::InvalidFormat("not a .npy file".to_string()));
+    }
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        major => {
+            return Err(Error::InvalidFormat(format!(
+                "unsupported npy version {}.{}",
+                major, preamble[7]
+            )))
+        }
+    };
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header)?;
+    let header = String::from_utf8_lossy(&header);
+    let (dtype, big_endian, fortran_order, shape) = parse_header(&header)?;
+
+    let size = shape
+        .iter()
+        .try_fold(dtype.size(), |acc, &d| acc.checked_mul(d))
+        .ok_or_else(|| Error::InvalidFormat(format!("npy shape {:?} is too large", shape)))?;
+    let mut bytes = vec![0u8; size];
+    reader.read_exact(&mut bytes)?;
+    let mut data = decode(&bytes, dtype, big_endian);
+    if fortran_order && shape.len() > 1 {
+        data = fortran_to_c(&data, &shape);
+    }
+    Ok(NpyArray { shape, dtype, data })
+}
+
+/// Writes only the `.npy` preamble; exactly `shape.iter().product()` values
+/// of `dtype` must follow.
+pub(crate) fn write_npy_header(
+    mut writer: impl Write,
+    shape: &[usize],
+    dtype: Dtype,
+) -> Result<()> {
+    let shape = match shape.len() {
+        1 => format!("({},)", shape[0]),
+        _ => format!(
+            "({})",
+            shape
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        dtype.descr(),
+        shape
+    );
+    // Magic, version and length take 10 bytes; the whole preamble is padded
+    // to a multiple of 64 and terminated by a newline.
+    let total = 10 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - total % 64) % 64));
+    header.push('\n');
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[1, 0])?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    Ok(())
+}
+
+pub fn write_npy_to(mut writer: impl Write, array: &NpyArray) -> Result<()> {
+    write_npy_header(&mut writer, &array.shape, array.dtype)?;
+    for &value in &array.data {
+        match array.dtype {
+            Dtype::F32 => writer.write_all(&(value as f32).to_le_bytes())?,
+            Dtype::F64 => writer.write_all(&value.to_le_bytes())?,
+            Dtype::I64 => writer.write_all(&(value as i64).to_le_bytes())?,
+        }
+    }
+    Ok(())
+}
+
+pub fn read_npy(path: impl AsRef<Path>) -> Result<NpyArray> {
+    read_npy_from(BufReader::new(File::open(path)?))
+}
+
+pub fn write_npy(path: impl AsRef<Path>, array: &NpyArray) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_npy_to(&mut writer, array)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads every array of an `.npz` archive (as written by `np.savez` or
+/// `np.savez_compressed`), keyed by name without the `.npy` suffix.
+pub fn read_npz(path: impl AsRef<Path>) -> Result<BTreeMap<String, NpyArray>> {
+    read_npz_from(BufReader::new(File::open(path)?))
+}
+
+pub fn read_npz_from(reader: impl Read + Seek) -> Result<BTreeMap<String, NpyArray>> {
+    let mut archive = ZipArchive::new(reader)?;
+    let mut arrays = BTreeMap::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+        let name = entry.name().trim_end_matches(".npy").to_string();
+        arrays.insert(name, read_npy_from(entry)?);
+    }
+    Ok(arrays)
+}
+
+/// Writes an uncompressed `.npz` archive, like `np.savez`.
+pub fn write_npz(path: impl AsRef<Path>, arrays: &[(&str, &NpyArray)]) -> Result<()> {
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(path)?));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    for (name, array) in arrays {
+        zip.start_file(format!("{}.npy", name), options)?;
+        write_npy_to(&mut zip, array)?;
+    }
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_round_trips() {
+        for (shape, dtype) in [
+            (vec![5], Dtype::I64),
+            (vec![3, 2], Dtype::F64),
+            (vec![4, 1, 7], Dtype::F32),
+            (vec![0, 2], Dtype::F64),
+        ] {
+            let mut bytes = Vec::new();
+            write_npy_header(&mut bytes, &shape, dtype).unwrap();
+            assert_eq!(bytes.len() % 64, 0);
+            let header = String::from_utf8(bytes[10..].to_vec()).unwrap();
+            assert_eq!(parse_header(&header).unwrap(), (dtype, false, false, shape));
+        }
+    }
+
+    #[test]
+    fn parses_numpy_written_headers() {
+        let header = "{'descr': '>f4', 'fortran_order': True, 'shape': (2, 3), }";
+        assert_eq!(
+            parse_header(header).unwrap(),
+            (Dtype::F32, true, true, vec![2, 3])
+        );
+        let complex = "{'descr': '<c16', 'fortran_order': False, 'shape': (2,), }";
+        assert!(parse_header(complex).is_err());
+    }
+
+    #[test]
+    fn fortran_order_becomes_row_major() {
+        // [[1, 2, 3], [4, 5, 6]] stored column by column.
+        let fortran = [1.0, 4.0, 2.0, 5.0, 3.0, 6.0];
+        assert_eq!(
+            fortran_to_c(&fortran, &[2, 3]),
+            vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+        // A (2, 2, 2) array whose values are their C-order offsets.
+        let fortran = [0.0, 4.0, 2.0, 6.0, 1.0, 5.0, 3.0, 7.0];
+        assert_eq!(
+            fortran_to_c(&fortran, &[2, 2, 2]),
+            (0..8).map(f64::from).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn arrays_round_trip() {
+        let points = vec![Point { x: 1.5, y: -2.0 }, Point { x: 0.0, y: 3.25 }];
+        let mut bytes = Vec::new();
+        write_npy_to(&mut bytes, &NpyArray::from_points(&points)).unwrap();
+        let array = read_npy_from(bytes.as_slice()).unwrap();
+        let read = array.to_points().unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!((read[1].x, read[1].y), (0.0, 3.25));
+
+        let mut bytes = Vec::new();
+        write_npy_to(&mut bytes, &NpyArray::from_labels(&[2, 0, 1])).unwrap();
+        let array = read_npy_from(bytes.as_slice()).unwrap();
+        assert_eq!(array.to_labels().unwrap(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn rejects_invalid_labels() {
+        for bad in [-1.0, 0.5, f64::NAN] {
+            let array = NpyArray {
+                shape: vec![2],
+                dtype: Dtype::F64,
+                data: vec![0.0, bad],
+            };
+            assert!(array.to_labels().is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let mut bytes = Vec::new();
+        write_npy_to(&mut bytes, &NpyArray::from_labels(&[1])).unwrap();
+        bytes[6] = 4;
+        assert!(matches!(
+            read_npy_from(bytes.as_slice()),
+            Err(Error::InvalidFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,135 @@
+use serde::Serialize;
+use std::fmt;
+
+use crate::scaling::ScalingResult;
+use crate::stats::t_critical_95;
+
+/// Karp–Flatt experimentally determined serial fraction at one thread count.
+#[derive(Debug, Clone, Serialize)]
+pub struct KarpFlatt {
+    pub threads: usize,
+    pub speedup: f64,
+    pub serial_fraction: f64,
+}
+
+/// Parallel fraction fitted to measured speedups.
+#[derive(Debug, Clone, Serialize)]
+pub struct FractionFit {
+    /// "amdahl" or "gustafson".
+    pub law: String,
+    /// Least-squares estimate; noise or overhead can push it outside [0, 1].
+    pub p: f64,
+    /// Whether `p` is a valid parallel fraction, i.e. lies in [0, 1].
+    pub in_range: bool,
+    pub std_error: f64,
+    /// 95% confidence interval for `p`, when there are at least two
+    /// multi-threaded measurements.
+    pub ci: Option<(f64, f64)>,
+    /// Number of measurements with more than one thread used in the fit.
+    pub n_points: usize,
+    pub karp_flatt: Vec<KarpFlatt>,
+}
+
+/// Fits the parallel fraction `p` of `law` to the measured speedups by least
+/// squares. Both laws are linear in `p` after a change of variables:
+///
+/// - Amdahl: `1 - 1/S = p (1 - 1/n)`
+/// - Gustafson: `S - 1 = p (n - 1)`
+///
+/// so `p` is the slope of a regression through the origin. Single-thread
+/// rows carry no information and are skipped. Returns `None` when no row
+/// has more than one thread. The slope is not constrained, so a `p` outside
+/// [0, 1] is reported as such rather than clamped.
+pub fn fit_parallel_fraction(results: &[ScalingResult], law: &str) -> Option<FractionFit> {
+    let pairs: Vec<(f64, f64)> = results
+        .iter()
+        .filter(|r| r.threads > 1)
+        .map(|r| {
+            let n = r.threads as f64;
+            let s = r.speedup;
+            if law == "gustafson" {
+                (n - 1.0, s - 1.0)
+            } else {
+                (1.0 - 1.0 / n, 1.0 - 1.0 / s)
+            }
+        })
+        .collect();
+    if pairs.is_empty() {
+        return None;
+    }
+
+    let sxx: f64 = pairs.iter().map(|(x, _)| x * x).sum();
+    let sxy: f64 = pairs.iter().map(|(x, y)| x * y).sum();
+    let p = sxy / sxx;
+
+    let m = pairs.len();
+    let (std_error, ci) = if m > 1 {
+        let ssr: f64 = pairs.iter().map(|(x, y)| (y - p * x).powi(2)).sum();
+        let se = (ssr / (m - 1) as f64 / sxx).sqrt();
+        let half = t_critical_95(m - 1) * se;
+        (se, Some((p - half, p + half)))
+    } else {
+        (f64::NAN, None)
+    };
+
+    Some(FractionFit {
+        law: law.to_string(),
+        p,
+        in_range: (0.0..=1.0).contains(&p),
+        std_error,
+        ci,
+        n_points: m,
+        karp_flatt: karp_flatt(results),
+    })
+}
+
+impl FractionFit {
+    /// `p` when it is a valid parallel fraction, for drawing the fitted curve.
+    pub fn valid_p(&self) -> Option<f64> {
+        self.in_range.then_some(self.p)
+    }
+}
+
+/// Karp–Flatt serial fraction `e = (1/S - 1/n) / (1 - 1/n)` for every
+/// measurement with more than one thread. A serial fraction that grows with
+/// `n` points to parallel overhead rather than inherently serial work.
+pub fn karp_flatt(results: &[ScalingResult]) -> Vec<KarpFlatt> {
+    results
+        .iter()
+        .filter(|r| r.threads > 1)
+        .map(|r| {
+            let n = r.threads as f64;
+            KarpFlatt {
+                threads: r.threads,
+                speedup: r.speedup,
+                serial_fraction: (1.0 / r.speedup - 1.0 / n) / (1.0 - 1.0 / n),
+            }
+        })
+        .collect()
+}
+
+impl fmt::Display for FractionFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fitted {} p = {:.4}", self.law, self.p)?;
+        if !self.in_range {
+            write!(f, " (outside [0, 1]; not a valid parallel fraction)")?;
+        }
+        match self.ci {
+            Some((lo, hi)) => writeln!(f, " (95% CI {:.4}..{:.4})", lo, hi)?,
+            None => writeln!(f, " (too few points for a CI)")?,
+        }
+        writeln!(
+            f,
+            "{:>8} {:>9} {:>14}",
+            "Threads", "Speedup", "Karp-Flatt e"
+        )?;
+        for kf in &self.karp_flatt {
+            writeln!(
+                f,
+                "{:>8} {:>9.3} {:>14.4}",
+                kf.threads, kf.speedup, kf.serial_fraction
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use crate::error::{Error, Result};
+use crate::point::Point;
+use arrow::array::{Array, ArrayRef, Float64Array, Int64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NullHandling {
+    Reject,
+    /// Replace nulls with the mean of the non-null values of the column.
+    ImputeMean,
+    ImputeValue(f64),
+}
+
+fn float_column(batch: &RecordBatch, name: &str) -> Result<Float64Array> {
+    let column = batch
+        .column_by_name(name)
+        .ok_or_else(|| Error::InvalidFormat(format!("no column named '{}'", name)))?;
+    if !column.data_type().is_numeric() {
+        return Err(Error::InvalidFormat(format!(
+            "column '{}' has non-numeric type {}",
+            name,
+            column.data_type()
+        )));
+    }
+    let column = cast(column, &DataType::Float64)?;
+    Ok(column
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap()
+        .clone())
+}
+
+fn fill_value(columns: &[Float64Array], name: &str, nulls: NullHandling) -> Result<f64> {
+    let null_count: usize = columns.iter().map(|c| c.null_count()).sum();
+    match nulls {
+        _ if null_count == 0 => Ok(0.0),
+        NullHandling::Reject => Err(Error::InvalidFormat(format!(
+            "column '{}' contains {} null values",
+            name, null_count
+        ))),
+        NullHandling::ImputeValue(value) => Ok(value),
+        NullHandling::ImputeMean => {
+            let (sum, count) = columns
+                .iter()
+                .flat_map(|c| c.iter().flatten())
+                .fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+            if count == 0 {
+                return Err(Error::InvalidFormat(format!(
+                    "column '{}' has no values to impute from",
+                    name
+                )));
+            }
+            Ok(sum / count as f64)
+        }
+    }
+}
+
+/// Builds points from two numeric columns across `batches`. Integer and
+/// float columns of any width are accepted.
+pub fn points_from_batches(
+    batches: &[RecordBatch],
+    x: &str,
+    y: &str,
+    nulls: NullHandling,
+) -> Result<Vec<Point>> {
+    let xs = batches
+        .iter()
+        .map(|b| float_column(b, x))
+        .collect::<Result<Vec<_>>>()?;
+    let ys = batches
+        .iter()
+        .map(|b| float_column(b, y))
+        .collect::<Result<Vec<_>>>()?;
+    let x_fill = fill_value(&xs, x, nulls)?;
+    let y_fill = fill_value(&ys, y, nulls)?;
+    Ok(xs
+        .iter()
+        .zip(ys.iter())
+        .flat_map(|(xc, yc)| {
+            xc.iter().zip(yc.iter()).map(|(xv, yv)| Point {
+                x: xv.unwrap_or(x_fill),
+                y: yv.unwrap_or(y_fill),
+            })
+        })
+        .collect())
+}
+
+pub fn points_from_batch(
+    batch: &RecordBatch,
+    x: &str,
+    y: &str,
+    nulls: NullHandling,
+) -> Result<Vec<Point>> {
+    points_from_batches(std::slice::from_ref(batch), x, y, nulls)
+}
+
+pub fn read_parquet(path: impl AsRef<Path>) -> Result<Vec<RecordBatch>> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?.build()?;
+    Ok(reader.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+/// Appends `labels` to `batches` as an int64 column and writes the result.
+pub fn write_parquet_with_labels(
+    path: impl AsRef<Path>,
+    batches: &[RecordBatch],
+    labels: &[usize],
+    label_column: &str,
+) -> Result<()> {
+    let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+    if rows != labels.len() {
+        return Err(Error::InvalidFormat(format!(
+            "{} labels for {} rows",
+            labels.len(),
+            rows
+        )));
+    }
+    let base = match batches.first() {
+        Some(batch) => batch.schema(),
+        None => Arc::new(Schema::empty()),
+    };
+    let mut fields: Vec<Field> = base.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields.push(Field::new(label_column, DataType::Int64, false));
+    let schema = Arc::new(Schema::new_with_metadata(fields, base.metadata().clone()));
+
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema.clone(), None)?;
+    let mut offset = 0;
+    for batch in batches {
+        let batch_labels = &labels[offset..offset + batch.num_rows()];
+        offset += batch.num_rows();
+        let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+        columns.push(Arc::new(Int64Array::from_iter_values(
+            batch_labels.iter().map(|&l| l as i64),
+        )));
+        writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+    }
+    writer.close()?;
+    Ok(())
+}
@@ -1,4 +1,8 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+// `repr(C)` lets a row-major f64 buffer with two columns be viewed as `[Point]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[repr(C)]
 pub struct Point {
     pub x: f64,
     pub y: f64,
@@ -26,6 +30,19 @@ pub fn euclidean_distance(a: &Point, b: &Point) -> f64 {
     ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
 }
 
+pub fn nearest_centroid(point: &Point, centroids: &[Point]) -> usize {
+    let mut min_dist = f64::MAX;
+    let mut cluster = 0;
+    for (j, centroid) in centroids.iter().enumerate() {
+        let dist = euclidean_distance(point, centroid);
+        if dist < min_dist {
+            min_dist = dist;
+            cluster = j;
+        }
+    }
+    cluster
+}
+
 // pub fn mean(points: &[Point]) -> Point {
 //     let n = points.len() as f64;
 //     let (sum_x, sum_y) = points
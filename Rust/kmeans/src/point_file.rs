@@ -0,0 +1,309 @@
+//! Binary point files that can be memory-mapped and clustered in place.
+//!
+//! Layout (all integers little-endian):
+//!
+//! | offset | size | field                                  |
+//! |--------|------|----------------------------------------|
+//! | 0      | 8    | magic `KMPOINTS`                       |
+//! | 8      | 4    | format version (u32, currently 1)      |
+//! | 12     | 4    | dtype (u32): 1 = f32, 2 = f64          |
+//! | 16     | 8    | number of points `n` (u64)             |
+//! | 24     | 8    | dimension `dim` (u64)                  |
+//! | 32     | 32   | reserved, zero                         |
+//! | 64     | ...  | `n * dim` little-endian values, row-major |
+//!
+//! The 64-byte header keeps the payload aligned, so a file with `dim = 2` and
+//! f64 values can be viewed directly as `&[Point]`.
+
+use crate::csv_io::{visit_csv, CsvOptions};
+use crate::error::{Error, Result};
+use crate::point::Point;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"KMPOINTS";
+const VERSION: u32 = 1;
+pub const HEADER_LEN: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    F32,
+    F64,
+}
+
+impl ValueType {
+    fn code(self) -> u32 {
+        match self {
+            ValueType::F32 => 1,
+            ValueType::F64 => 2,
+        }
+    }
+
+    fn from_code(code: u32) -> Result<Self> {
+        match code {
+            1 => Ok(ValueType::F32),
+            2 => Ok(ValueType::F64),
+            _ => Err(Error::InvalidFormat(format!("unknown dtype code {}", code))),
+        }
+    }
+
+    pub fn size(self) -> usize {
+        match self {
+            ValueType::F32 => 4,
+            ValueType::F64 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointFileHeader {
+    pub n: u64,
+    pub dim: u64,
+    pub dtype: ValueType,
+}
+
+impl PointFileHeader {
+    fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[..8].copy_from_slice(MAGIC);
+        bytes[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.dtype.code().to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.n.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.dim.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return Err(Error::InvalidFormat("not a k-means point file".to_string()));
+        }
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(Error::IncompatibleVersion {
+                found: version,
+                supported: VERSION,
+            });
+        }
+        let header = PointFileHeader {
+            dtype: ValueType::from_code(u32::from_le_bytes(bytes[12..16].try_into().unwrap()))?,
+            n: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+            dim: u64::from_le_bytes(bytes[24..32].try_into().unwrap()),
+        };
+        header.payload_len()?;
+        Ok(header)
+    }
+
+    fn too_large(&self) -> Error {
+        Error::InvalidFormat(format!(
+            "{} points of dimension {} do not fit in memory",
+            self.n, self.dim
+        ))
+    }
+
+    /// Bytes per point; an error if it overflows `usize`.
+    pub fn row_len(&self) -> Result<usize> {
+        usize::try_from(self.dim)
+            .ok()
+            .and_then(|dim| dim.checked_mul(self.dtype.size()))
+            .ok_or_else(|| self.too_large())
+    }
+
+    /// Bytes of all points, header excluded; an error if it overflows
+    /// `HEADER_LEN + usize`.
+    pub fn payload_len(&self) -> Result<usize> {
+        let row_len = self.row_len()?;
+        usize::try_from(self.n)
+            .ok()
+            .and_then(|n| n.checked_mul(row_len))
+            .filter(|len| len.checked_add(HEADER_LEN).is_some())
+            .ok_or_else(|| self.too_large())
+    }
+
+    /// Decodes whole rows of payload bytes, keeping the first two columns.
+    pub fn decode_rows(&self, bytes: &[u8]) -> Result<Vec<Point>> {
+        let dim = self.dim as usize;
+        if dim < 2 {
+            return Err(Error::InvalidFormat(format!(
+                "need at least 2 columns, found {}",
+                dim
+            )));
+        }
+        let size = self.dtype.size();
+        let row_len = self.row_len()?;
+        let value = |bytes: &[u8]| match self.dtype {
+            ValueType::F32 => f32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            ValueType::F64 => f64::from_le_bytes(bytes.try_into().unwrap()),
+        };
+        Ok(bytes
+            .chunks_exact(row_len)
+            .map(|row| Point {
+                x: value(&row[..size]),
+                y: value(&row[size..2 * size]),
+            })
+            .collect())
+    }
+
+    pub fn read_from(mut reader: impl Read) -> Result<Self> {
+        let mut bytes = [0u8; HEADER_LEN];
+        reader.read_exact(&mut bytes)?;
+        PointFileHeader::from_bytes(&bytes)
+    }
+}
+
+/// A memory-mapped point file.
+pub struct MappedPoints {
+    header: PointFileHeader,
+    payload_len: usize,
+    mmap: Mmap,
+}
+
+impl MappedPoints {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        // Safety: the mapping is read-only; the file must not be truncated
+        // by another process while it is mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let header = PointFileHeader::from_bytes(&mmap)?;
+        let payload_len = header.payload_len()?;
+        if mmap.len() - HEADER_LEN < payload_len {
+            return Err(Error::InvalidFormat(format!(
+                "file holds {} payload bytes, header promises {}",
+                mmap.len() - HEADER_LEN,
+                payload_len
+            )));
+        }
+        Ok(MappedPoints {
+            header,
+            payload_len,
+            mmap,
+        })
+    }
+
+    pub fn header(&self) -> PointFileHeader {
+        self.header
+    }
+
+    pub fn len(&self) -> usize {
+        self.header.n as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.header.n == 0
+    }
+
+    fn payload(&self) -> &[u8] {
+        &self.mmap[HEADER_LEN..HEADER_LEN + self.payload_len]
+    }
+
+    /// Views a two-dimensional f64 file as points without copying, ready to
+    /// be passed to `kmeans_par`.
+    pub fn as_points(&self) -> Result<&[Point]> {
+        if self.header.dim != 2 || self.header.dtype != ValueType::F64 {
+            return Err(Error::InvalidFormat(format!(
+                "only dim = 2 f64 files can be viewed as points (found dim = {}, {:?})",
+                self.header.dim, self.header.dtype
+            )));
+        }
+        if cfg!(target_endian = "big") {
+            return Err(Error::InvalidFormat(
+                "point files cannot be mapped on big-endian targets".to_string(),
+            ));
+        }
+        let payload = self.payload();
+        if payload.as_ptr().align_offset(std::mem::align_of::<Point>()) != 0 {
+            return Err(Error::InvalidFormat("payload is not aligned".to_string()));
+        }
+        // Safety: `Point` is `repr(C)` with two f64 fields, the payload is
+        // aligned and the length is taken from its size, not the header.
+        let len = payload.len() / std::mem::size_of::<Point>();
+        Ok(unsafe { std::slice::from_raw_parts(payload.as_ptr() as *const Point, len) })
+    }
+
+    /// Copies the first two columns into owned points; works for any
+    /// dimension (at least 2) and either dtype.
+    pub fn to_points(&self) -> Result<Vec<Point>> {
+        self.header.decode_rows(self.payload())
+    }
+}
+
+/// Streams points into a point file; the point count in the header is
+/// filled in by `finish`.
+pub struct PointFileWriter {
+    writer: BufWriter<File>,
+    n: u64,
+}
+
+impl PointFileWriter {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let header = PointFileHeader {
+            n: 0,
+            dim: 2,
+            dtype: ValueType::F64,
+        };
+        writer.write_all(&header.to_bytes())?;
+        Ok(PointFileWriter { writer, n: 0 })
+    }
+
+    pub fn write(&mut self, point: &Point) -> Result<()> {
+        self.writer.write_all(&point.x.to_le_bytes())?;
+        self.writer.write_all(&point.y.to_le_bytes())?;
+        self.n += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<u64> {
+        let header = PointFileHeader {
+            n: self.n,
+            dim: 2,
+            dtype: ValueType::F64,
+        };
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&header.to_bytes())?;
+        self.writer.flush()?;
+        Ok(self.n)
+    }
+}
+
+pub fn write_point_file(path: impl AsRef<Path>, points: &[Point]) -> Result<()> {
+    let mut writer = PointFileWriter::create(path)?;
+    for point in points {
+        writer.write(point)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Converts a CSV file to a point file row by row, without holding the data
+/// in memory. Returns the number of points written.
+pub fn convert_csv(
+    csv_path: impl AsRef<Path>,
+    out_path: impl AsRef<Path>,
+    options: &CsvOptions,
+) -> Result<u64> {
+    let mut writer = PointFileWriter::create(out_path)?;
+    visit_csv(csv_path, options, |_, point| writer.write(&point))?;
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_overflowing_headers() {
+        for (n, dim) in [(1u64 << 60, 2u64), (1, u64::MAX), (u64::MAX, 1)] {
+            let header = PointFileHeader {
+                n,
+                dim,
+                dtype: ValueType::F64,
+            };
+            assert!(matches!(
+                PointFileHeader::from_bytes(&header.to_bytes()),
+                Err(Error::InvalidFormat(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use crate::scaling::{ScalingResult, TimingStats};
+use crate::stats::mann_whitney_greater;
+use std::fmt;
+
+/// When a parallel timing counts as a regression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegressionConfig {
+    /// Relative slowdown of the parallel time tolerated, e.g. 0.05 for 5%.
+    pub max_slowdown: f64,
+    /// Significance level of a one-sided Mann–Whitney test on the raw
+    /// samples that must also reject; `None` uses the slowdown alone.
+    pub alpha: Option<f64>,
+}
+
+impl Default for RegressionConfig {
+    fn default() -> Self {
+        RegressionConfig {
+            max_slowdown: 0.05,
+            alpha: None,
+        }
+    }
+}
+
+/// One configuration measured in both the baseline and the current run.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub threads: usize,
+    pub n_points: usize,
+    pub k: usize,
+    /// Parallel seconds: the median when known, otherwise the mean.
+    pub baseline: f64,
+    pub current: f64,
+    /// `current / baseline - 1`.
+    pub change: f64,
+    /// Mann–Whitney p-value, when a test was requested and both runs kept
+    /// their raw samples.
+    pub p_value: Option<f64>,
+    pub regressed: bool,
+}
+
+/// Comparisons of every configuration, plus the current configurations the
+/// baseline lacks.
+#[derive(Debug, Clone)]
+pub struct RegressionReport {
+    pub comparisons: Vec<Comparison>,
+    pub unmatched: usize,
+}
+
+impl RegressionReport {
+    pub fn regressed(&self) -> bool {
+        self.comparisons.iter().any(|c| c.regressed)
+    }
+}
+
+fn central(stats: &TimingStats) -> f64 {
+    if stats.median.is_finite() {
+        stats.median
+    } else {
+        stats.mean
+    }
+}
+
+// Older result files carry no problem size, so zero matches anything.
+fn same_config(a: &ScalingResult, b: &ScalingResult) -> bool {
+    let matches = |x: usize, y: usize| x == 0 || y == 0 || x == y;
+    a.threads == b.threads && a.dim == b.dim && matches(a.n_points, b.n_points) && matches(a.k, b.k)
+}
+
+/// Compares the parallel times of `current` against `baseline`,
+/// configuration by configuration.
+pub fn compare(
+    baseline: &[ScalingResult],
+    current: &[ScalingResult],
+    config: &RegressionConfig,
+) -> RegressionReport {
+    let mut comparisons = Vec::new();
+    let mut unmatched = 0;
+    for cur in current {
+        let Some(base) = baseline.iter().find(|b| same_config(b, cur)) else {
+            unmatched += 1;
+            continue;
+        };
+        let (before, after) = (central(&base.par), central(&cur.par));
+        let change = after / before - 1.0;
+        let p_value = config
+            .alpha
+            .filter(|_| !base.par.times.is_empty() && !cur.par.times.is_empty())
+            .map(|_| mann_whitney_greater(&cur.par.times, &base.par.times));
+        let significant = match (config.alpha, p_value) {
+            (Some(alpha), Some(p)) => p < alpha,
+            _ => true,
+        };
+        comparisons.push(Comparison {
+            threads: cur.threads,
+            n_points: cur.n_points,
+            k: cur.k,
+            baseline: before,
+            current: after,
+            change,
+            p_value,
+            regressed: change > config.max_slowdown && significant,
+        });
+    }
+    RegressionReport {
+        comparisons,
+        unmatched,
+    }
+}
+
+impl fmt::Display for RegressionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>8} {:>10} {:>6} {:>12} {:>12} {:>9} {:>8}  Status",
+            "Threads", "Points", "K", "Baseline(s)", "Current(s)", "Change", "p"
+        )?;
+        for c in &self.comparisons {
+            let p = c
+                .p_value
+                .map_or_else(|| "-".to_string(), |p| format!("{:.4}", p));
+            writeln!(
+                f,
+                "{:>8} {:>10} {:>6} {:>12.4} {:>12.4} {:>+8.1}% {:>8}  {}",
+                c.threads,
+                c.n_points,
+                c.k,
+                c.baseline,
+                c.current,
+                c.change * 100.0,
+                p,
+                if c.regressed { "SLOWER" } else { "ok" }
+            )?;
+        }
+        if self.unmatched > 0 {
+            writeln!(
+                f,
+                "{} configurations have no baseline to compare with",
+                self.unmatched
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kmeans_run::PhaseTotals;
+
+    fn result(threads: usize, n_points: usize, k: usize, par_times: &[f64]) -> ScalingResult {
+        let stats = |times: &[f64]| TimingStats::from_times(times.to_vec(), 0, 0);
+        ScalingResult {
+            threads,
+            n_points,
+            k,
+            dim: 2,
+            seq: stats(&[1.0]),
+            par: stats(par_times),
+            speedup: 1.0,
+            efficiency: 1.0,
+            median_speedup: None,
+            seq_iterations: vec![],
+            par_iterations: vec![],
+            seq_phases: PhaseTotals::default(),
+            par_phases: PhaseTotals::default(),
+        }
+    }
+
+    #[test]
+    fn zero_size_matches_any_size() {
+        let known = result(4, 1000, 8, &[1.0]);
+        assert!(same_config(&known, &result(4, 0, 8, &[1.0])));
+        assert!(same_config(&known, &result(4, 1000, 0, &[1.0])));
+        assert!(same_config(&result(4, 0, 0, &[1.0]), &known));
+        assert!(!same_config(&known, &result(2, 1000, 8, &[1.0])));
+        assert!(!same_config(&known, &result(4, 2000, 8, &[1.0])));
+        assert!(!same_config(&known, &result(4, 1000, 16, &[1.0])));
+        let mut wider = known.clone();
+        wider.dim = 3;
+        assert!(!same_config(&known, &wider));
+    }
+
+    #[test]
+    fn flags_slowdowns_beyond_the_limit() {
+        let baseline = [result(1, 1000, 4, &[1.0]), result(2, 1000, 4, &[1.0])];
+        let current = [
+            result(1, 1000, 4, &[1.04]),
+            result(2, 1000, 4, &[1.2]),
+            result(4, 1000, 4, &[9.0]),
+        ];
+        let report = compare(&baseline, &current, &RegressionConfig::default());
+        assert_eq!(report.unmatched, 1);
+        let flags: Vec<bool> = report.comparisons.iter().map(|c| c.regressed).collect();
+        assert_eq!(flags, vec![false, true]);
+        assert!((report.comparisons[1].change - 0.2).abs() < 1e-12);
+        assert!(report.regressed());
+
+        let faster = [result(1, 1000, 4, &[0.5]), result(2, 1000, 4, &[0.9])];
+        assert!(!compare(&baseline, &faster, &RegressionConfig::default()).regressed());
+    }
+
+    #[test]
+    fn different_sizes_compare_nothing() {
+        let baseline = [result(2, 1000, 4, &[1.0])];
+        let current = [result(2, 2000, 4, &[9.0])];
+        let report = compare(&baseline, &current, &RegressionConfig::default());
+        assert!(report.comparisons.is_empty());
+        assert_eq!(report.unmatched, 1);
+        assert!(!report.regressed());
+    }
+
+    #[test]
+    fn legacy_baseline_without_sizes_still_compares() {
+        let baseline = [result(2, 0, 0, &[1.0])];
+        let current = [result(2, 5000, 4, &[1.5])];
+        let report = compare(&baseline, &current, &RegressionConfig::default());
+        assert_eq!(report.unmatched, 0);
+        assert!(report.regressed());
+    }
+
+    #[test]
+    fn significance_test_can_clear_a_slowdown() {
+        let config = RegressionConfig {
+            max_slowdown: 0.05,
+            alpha: Some(0.05),
+        };
+        // Medians 20% apart but the samples overlap heavily.
+        let baseline = [result(1, 1000, 4, &[1.0, 1.5, 0.5, 1.4, 0.6])];
+        let noisy = [result(1, 1000, 4, &[1.2, 0.6, 1.6, 0.5, 1.3])];
+        let report = compare(&baseline, &noisy, &config);
+        assert!(report.comparisons[0].change > 0.05);
+        assert!(report.comparisons[0].p_value.unwrap() >= 0.05);
+        assert!(!report.regressed());
+
+        // Every current sample slower than every baseline sample.
+        let before = [1.0, 1.01, 0.99, 1.02, 0.98, 1.0, 1.01, 0.99];
+        let after = before.map(|t| t + 0.5);
+        let baseline = [result(1, 1000, 4, &before)];
+        let slower = [result(1, 1000, 4, &after)];
+        let report = compare(&baseline, &slower, &config);
+        assert!(report.comparisons[0].p_value.unwrap() < 0.05);
+        assert!(report.regressed());
+    }
+}
@@ -0,0 +1,313 @@
+use clap::Parser;
+use kmeans::error::{Error, Result};
+use kmeans::scaling::{load_results, ScalingResult};
+use kmeans::scaling_plots::{plot_scaling_series, plot_times, PlotOptions, Series};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Compare Rust and Python scaling results side by side.
+#[derive(Parser)]
+#[command(name = "report")]
+struct Cli {
+    /// Directory with the Python result CSVs
+    #[arg(long, default_value = "../../Python/experiments_files")]
+    python: PathBuf,
+    /// Directory with the Rust result CSVs
+    #[arg(long, default_value = "experiments_files")]
+    rust: PathBuf,
+    /// Directory for the plots and the report
+    #[arg(long, default_value = "experiments_files/report")]
+    out: PathBuf,
+}
+
+// One result file, named after what follows `{kind}_scaling_` in its stem.
+struct ResultFile {
+    language: &'static str,
+    label: String,
+    results: Vec<ScalingResult>,
+}
+
+const KINDS: [(&str, &str, &str); 2] = [
+    ("strong", "Strong scaling", "amdahl"),
+    ("weak", "Weak scaling", "gustafson"),
+];
+
+// Reads every `{kind}_scaling*.csv` in `dir`, sorted by name.
+fn load_dir(dir: &Path, language: &'static str, kind: &str) -> Result<Vec<ResultFile>> {
+    let prefix = format!("{}_scaling", kind);
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    paths.sort();
+    let mut files = Vec::new();
+    for path in paths {
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if path.extension().is_none_or(|e| e != "csv") || !stem.starts_with(&prefix) {
+            continue;
+        }
+        let suffix = stem[prefix.len()..].trim_start_matches('_');
+        let label = if suffix.is_empty() {
+            language.to_lowercase()
+        } else {
+            suffix.to_string()
+        };
+        files.push(ResultFile {
+            language,
+            label,
+            results: load_results(&path)?,
+        });
+    }
+    Ok(files)
+}
+
+// Rows of `value` for every thread count any file has, one column per file.
+fn aligned(
+    files: &[ResultFile],
+    value: impl Fn(&ScalingResult) -> f64,
+) -> (Vec<usize>, Vec<Vec<Option<f64>>>) {
+    let threads: BTreeSet<usize> = files
+        .iter()
+        .flat_map(|f| f.results.iter().map(|r| r.threads))
+        .collect();
+    let rows = threads
+        .iter()
+        .map(|&t| {
+            files
+                .iter()
+                .map(|f| f.results.iter().find(|r| r.threads == t).map(&value))
+                .collect()
+        })
+        .collect();
+    (threads.into_iter().collect(), rows)
+}
+
+// Threads, points, k and dimension of a result whose file recorded its
+// problem size; older files leave the size zero.
+type Config = (usize, usize, usize, usize);
+
+fn config(r: &ScalingResult) -> Option<Config> {
+    (r.n_points > 0 && r.k > 0).then_some((r.threads, r.n_points, r.k, r.dim))
+}
+
+// Fastest mean parallel time of one language for each known configuration.
+fn best_times(files: &[ResultFile], language: &str) -> BTreeMap<Config, f64> {
+    let mut best = BTreeMap::new();
+    for r in files
+        .iter()
+        .filter(|f| f.language == language)
+        .flat_map(|f| &f.results)
+    {
+        if let Some(key) = config(r) {
+            best.entry(key)
+                .and_modify(|t: &mut f64| *t = t.min(r.par.mean))
+                .or_insert(r.par.mean);
+        }
+    }
+    best
+}
+
+struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+fn cell(value: Option<f64>, precision: usize) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{:.*}", precision, v))
+}
+
+fn value_table(
+    files: &[ResultFile],
+    value: impl Fn(&ScalingResult) -> f64,
+    precision: usize,
+) -> Table {
+    let (threads, rows) = aligned(files, value);
+    let mut header = vec!["Threads".to_string()];
+    header.extend(
+        files
+            .iter()
+            .map(|f| format!("{} ({})", f.label, f.language)),
+    );
+    let rows = threads
+        .iter()
+        .zip(rows)
+        .map(|(t, row)| {
+            let mut cells = vec![t.to_string()];
+            cells.extend(row.into_iter().map(|v| cell(v, precision)));
+            cells
+        })
+        .collect();
+    Table { header, rows }
+}
+
+// Python and Rust times of the configurations both languages measured with
+// the same threads, points, k and dimension.
+fn comparison_table(files: &[ResultFile]) -> Table {
+    let python = best_times(files, "Python");
+    let rust = best_times(files, "Rust");
+    let rows = python
+        .iter()
+        .filter_map(|(key, &p)| rust.get(key).map(|&r| (key, p, r)))
+        .map(|(&(threads, n_points, k, _), p, r)| {
+            vec![
+                threads.to_string(),
+                n_points.to_string(),
+                k.to_string(),
+                cell(Some(p), 4),
+                cell(Some(r), 4),
+                cell(Some(p / r), 2),
+            ]
+        })
+        .collect();
+    Table {
+        header: [
+            "Threads",
+            "Points",
+            "K",
+            "Python (s)",
+            "Rust (s)",
+            "Python / Rust",
+        ]
+        .map(String::from)
+        .to_vec(),
+        rows,
+    }
+}
+
+const NO_COMPARISON: &str = "No configuration was measured in both languages with the same \
+    number of points and clusters. Result files without NPoints and K columns \
+    cannot be compared; Python/scaling_experiments.py writes them from now on, so \
+    rerun it to regenerate older Python results.";
+
+fn markdown(table: &Table, out: &mut String) {
+    let _ = writeln!(out, "| {} |", table.header.join(" | "));
+    let _ = writeln!(out, "|{}", "---|".repeat(table.header.len()));
+    for row in &table.rows {
+        let _ = writeln!(out, "| {} |", row.join(" | "));
+    }
+    out.push('\n');
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn html(table: &Table, out: &mut String) {
+    out.push_str("<table>\n<tr>");
+    for h in &table.header {
+        let _ = write!(out, "<th>{}</th>", escape(h));
+    }
+    out.push_str("</tr>\n");
+    for row in &table.rows {
+        out.push_str("<tr>");
+        for c in row {
+            let _ = write!(out, "<td>{}</td>", escape(c));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+fn run(cli: Cli) -> Result<()> {
+    for dir in [&cli.python, &cli.rust] {
+        if !dir.is_dir() {
+            return Err(Error::InvalidFormat(format!(
+                "{} is not a directory",
+                dir.display()
+            )));
+        }
+    }
+    fs::create_dir_all(&cli.out)?;
+    let mut md = String::from("# Rust vs Python scaling\n\n");
+    let mut page = String::from(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Rust vs Python scaling</title></head>\n<body>\n<h1>Rust vs Python scaling</h1>\n",
+    );
+
+    for (kind, title, law) in KINDS {
+        let mut files = load_dir(&cli.python, "Python", kind)?;
+        files.extend(load_dir(&cli.rust, "Rust", kind)?);
+        if files.is_empty() {
+            continue;
+        }
+        println!("{}: {} result files", title, files.len());
+        let series: Vec<Series> = files
+            .iter()
+            .map(|f| Series {
+                label: format!("{} ({})", f.label, f.language),
+                results: &f.results,
+                fitted_p: None,
+            })
+            .collect();
+        let times_plot = format!("{}_times.svg", kind);
+        let speedup_plot = format!("{}_speedup.svg", kind);
+        plot_times(cli.out.join(&times_plot), &series)?;
+        plot_scaling_series(
+            cli.out.join(&speedup_plot),
+            &series,
+            law,
+            0.9,
+            &PlotOptions::default(),
+        )?;
+
+        let comparison = comparison_table(&files);
+        let sections = [
+            (
+                "Mean parallel time (s)",
+                value_table(&files, |r| r.par.mean, 4),
+            ),
+            (
+                "Mean sequential time (s)",
+                value_table(&files, |r| r.seq.mean, 4),
+            ),
+            ("Speedup", value_table(&files, |r| r.speedup, 2)),
+            ("Efficiency", value_table(&files, |r| r.efficiency, 2)),
+        ];
+
+        let _ = writeln!(md, "## {}\n", title);
+        let _ = writeln!(md, "![{} times]({})\n", title, times_plot);
+        let _ = writeln!(md, "![{} speedup]({})\n", title, speedup_plot);
+        let _ = writeln!(page, "<h2>{}</h2>", title);
+        let _ = writeln!(page, "<img src=\"{}\" alt=\"{} times\">", times_plot, title);
+        let _ = writeln!(
+            page,
+            "<img src=\"{}\" alt=\"{} speedup\">",
+            speedup_plot, title
+        );
+        let _ = writeln!(md, "### Python vs Rust\n");
+        let _ = writeln!(page, "<h3>Python vs Rust</h3>");
+        if comparison.rows.is_empty() {
+            let _ = writeln!(md, "{}\n", NO_COMPARISON);
+            let _ = writeln!(page, "<p>{}</p>", escape(NO_COMPARISON));
+        } else {
+            markdown(&comparison, &mut md);
+            html(&comparison, &mut page);
+        }
+        for (heading, table) in &sections {
+            let _ = writeln!(md, "### {}\n", heading);
+            markdown(table, &mut md);
+            let _ = writeln!(page, "<h3>{}</h3>", heading);
+            html(table, &mut page);
+        }
+    }
+    page.push_str("</body>\n</html>\n");
+    fs::write(cli.out.join("report.md"), md)?;
+    fs::write(cli.out.join("report.html"), page)?;
+    println!("Report written to {}", cli.out.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
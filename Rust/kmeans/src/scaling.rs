@@ -0,0 +1,593 @@
+use crate::environment::Environment;
+use crate::error::{Error, Result};
+use crate::generators::DatasetSpec;
+use crate::kmeans_nd::{kmeans_nd_par, kmeans_nd_seq};
+use crate::kmeans_run::PhaseTotals;
+use crate::point::Point;
+use crate::stats::{bootstrap_median_ci, mean_std, median, quantile, tukey_outliers};
+use crate::{kmeans_par, kmeans_seq};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// How each configuration is measured.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchConfig {
+    /// Untimed fits run before the measured ones.
+    pub warmup: usize,
+    pub repetitions: usize,
+    /// Run exactly `max_iters` iterations instead of stopping at the
+    /// tolerance, so convergence differences don't affect the speedup.
+    pub fixed_iterations: bool,
+    /// Resamples for the bootstrap confidence interval of the median.
+    pub bootstrap_resamples: usize,
+    /// Seed for the dataset, the initial centroids and the bootstrap.
+    pub seed: u64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup: 1,
+            repetitions: 5,
+            fixed_iterations: false,
+            bootstrap_resamples: 1000,
+            seed: 0,
+        }
+    }
+}
+
+/// Raw wall-clock samples of one configuration and their summary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingStats {
+    pub times: Vec<f64>,
+    pub mean: f64,
+    pub std: f64,
+    pub median: f64,
+    #[serde(default)]
+    pub iqr: f64,
+    /// Bootstrap 95% confidence interval of the median.
+    #[serde(default)]
+    pub median_ci: (f64, f64),
+    /// Indices into `times` outside Tukey's fences.
+    #[serde(default)]
+    pub outliers: Vec<usize>,
+}
+
+impl TimingStats {
+    pub fn from_times(times: Vec<f64>, bootstrap_resamples: usize, seed: u64) -> Self {
+        let (mean, std) = mean_std(&times);
+        let median = median(&times);
+        let iqr = quantile(&times, 0.75) - quantile(&times, 0.25);
+        let median_ci = bootstrap_median_ci(&times, bootstrap_resamples, seed);
+        let outliers = tukey_outliers(&times);
+        TimingStats {
+            times,
+            mean,
+            std,
+            median,
+            iqr,
+            median_ci,
+            outliers,
+        }
+    }
+}
+
+/// One thread count of a strong or weak scaling experiment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalingResult {
+    pub threads: usize,
+    pub n_points: usize,
+    pub k: usize,
+    /// Coordinates per point; 2 everywhere except the dimension sweep.
+    #[serde(default = "two")]
+    pub dim: usize,
+    pub seq: TimingStats,
+    pub par: TimingStats,
+    /// Ratio of the mean sequential and parallel times, as in the older
+    /// result files and the Python results.
+    pub speedup: f64,
+    /// `speedup` per thread.
+    pub efficiency: f64,
+    /// Ratio of the median sequential and parallel times; less sensitive to
+    /// outlying repetitions. Unknown for the older result files.
+    #[serde(default)]
+    pub median_speedup: Option<f64>,
+    /// Iterations executed by each sequential and parallel run.
+    pub seq_iterations: Vec<usize>,
+    pub par_iterations: Vec<usize>,
+    /// Mean seconds per run spent in each phase of the sequential and
+    /// parallel fits.
+    #[serde(default)]
+    pub seq_phases: PhaseTotals,
+    #[serde(default)]
+    pub par_phases: PhaseTotals,
+}
+
+fn two() -> usize {
+    2
+}
+
+// Runs the warm-up and timed repetitions of a sequential and a parallel fit.
+// Each fit returns its iteration count and, when recorded, its phase totals.
+fn sample(
+    threads: usize,
+    n_points: usize,
+    k: usize,
+    dim: usize,
+    bench: &BenchConfig,
+    seq_fit: impl Fn() -> (usize, Option<PhaseTotals>),
+    par_fit: impl Fn() -> (usize, Option<PhaseTotals>),
+) -> ScalingResult {
+    for _ in 0..bench.warmup {
+        seq_fit();
+        par_fit();
+    }
+
+    let mut seq_times = Vec::new();
+    let mut par_times = Vec::new();
+    let mut seq_iterations = Vec::new();
+    let mut par_iterations = Vec::new();
+    let mut seq_phases = Vec::new();
+    let mut par_phases = Vec::new();
+    for _ in 0..bench.repetitions {
+        let start_seq = Instant::now();
+        let (iterations, phases) = seq_fit();
+        seq_times.push(start_seq.elapsed().as_secs_f64());
+        seq_iterations.push(iterations);
+        seq_phases.extend(phases);
+
+        let start_par = Instant::now();
+        let (iterations, phases) = par_fit();
+        par_times.push(start_par.elapsed().as_secs_f64());
+        par_iterations.push(iterations);
+        par_phases.extend(phases);
+    }
+    let seq = TimingStats::from_times(seq_times, bench.bootstrap_resamples, bench.seed);
+    let par = TimingStats::from_times(par_times, bench.bootstrap_resamples, bench.seed);
+    let speedup = seq.mean / par.mean;
+    let efficiency = speedup / threads as f64;
+    let median_speedup = Some(seq.median / par.median);
+    ScalingResult {
+        threads,
+        n_points,
+        k,
+        dim,
+        seq,
+        par,
+        speedup,
+        efficiency,
+        median_speedup,
+        seq_iterations,
+        par_iterations,
+        seq_phases: PhaseTotals::mean(&seq_phases),
+        par_phases: PhaseTotals::mean(&par_phases),
+    }
+}
+
+fn effective_tolerance(tolerance: f64, bench: &BenchConfig) -> f64 {
+    if bench.fixed_iterations {
+        0.0
+    } else {
+        tolerance
+    }
+}
+
+// Times sequential and parallel fits of the same data with `threads` worker
+// threads. The data, initial centroids and thread pool are prepared once,
+// outside the timed region.
+fn measure(
+    dataset: &DatasetSpec,
+    n_points: usize,
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    threads: usize,
+    bench: &BenchConfig,
+) -> Result<ScalingResult> {
+    let points = dataset.generate(n_points, bench.seed);
+    let initial_centroids: Vec<Point> = points
+        .choose_multiple(&mut StdRng::seed_from_u64(bench.seed), k)
+        .cloned()
+        .collect();
+    let tolerance = effective_tolerance(tolerance, bench);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()?;
+    let seq_fit = || {
+        let run = kmeans_seq::kmeans_seq_timed(
+            &points,
+            k,
+            max_iters,
+            tolerance,
+            Some(initial_centroids.clone()),
+        );
+        (run.iterations, run.timings.map(|t| t.totals()))
+    };
+    let par_fit = || {
+        let run = pool.install(|| {
+            kmeans_par::kmeans_par_timed(
+                &points,
+                k,
+                max_iters,
+                tolerance,
+                Some(initial_centroids.clone()),
+            )
+        });
+        (run.iterations, run.timings.map(|t| t.totals()))
+    };
+    Ok(sample(threads, n_points, k, 2, bench, seq_fit, par_fit))
+}
+
+pub fn strong_scaling(
+    dataset: &DatasetSpec,
+    n_points: usize,
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    threads_list: &[usize],
+    bench: &BenchConfig,
+) -> Result<Vec<ScalingResult>> {
+    threads_list
+        .iter()
+        .map(|&threads| measure(dataset, n_points, k, max_iters, tolerance, threads, bench))
+        .collect()
+}
+
+pub fn weak_scaling(
+    dataset: &DatasetSpec,
+    base_points: usize,
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    threads_list: &[usize],
+    bench: &BenchConfig,
+) -> Result<Vec<ScalingResult>> {
+    threads_list
+        .iter()
+        .map(|&threads| {
+            let n_points = base_points * threads;
+            measure(dataset, n_points, k, max_iters, tolerance, threads, bench)
+        })
+        .collect()
+}
+
+/// One line per configuration whose samples contain outliers, for printing
+/// after a run.
+pub fn outlier_warnings(results: &[ScalingResult]) -> Vec<String> {
+    results
+        .iter()
+        .filter(|r| !r.seq.outliers.is_empty() || !r.par.outliers.is_empty())
+        .map(|r| {
+            format!(
+                "{} threads, k = {}: {} of {} sequential and {} of {} parallel samples are outliers",
+                r.threads,
+                r.k,
+                r.seq.outliers.len(),
+                r.seq.times.len(),
+                r.par.outliers.len(),
+                r.par.times.len()
+            )
+        })
+        .collect()
+}
+
+/// Sweeps the number of clusters at a fixed thread count, so the results
+/// show how the sequential/parallel gap changes with k.
+pub fn k_scaling(
+    dataset: &DatasetSpec,
+    n_points: usize,
+    ks: &[usize],
+    max_iters: usize,
+    tolerance: f64,
+    threads: usize,
+    bench: &BenchConfig,
+) -> Result<Vec<ScalingResult>> {
+    ks.iter()
+        .map(|&k| measure(dataset, n_points, k, max_iters, tolerance, threads, bench))
+        .collect()
+}
+
+/// Sweeps the number of coordinates per point at a fixed thread count and
+/// k, with the N-dimensional kernels of [`crate::kmeans_nd`], which record
+/// no phase timings. Only datasets with an N-dimensional version (uniform
+/// and blobs) can be swept.
+#[allow(clippy::too_many_arguments)]
+pub fn dim_scaling(
+    dataset: &DatasetSpec,
+    n_points: usize,
+    k: usize,
+    dims: &[usize],
+    max_iters: usize,
+    tolerance: f64,
+    threads: usize,
+    bench: &BenchConfig,
+) -> Result<Vec<ScalingResult>> {
+    let tolerance = effective_tolerance(tolerance, bench);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()?;
+    let mut results = Vec::new();
+    for &dim in dims {
+        let data = dataset
+            .generate_nd(n_points, dim, bench.seed)
+            .ok_or_else(|| {
+                Error::InvalidFormat(format!("{:?} has no {}-dimensional version", dataset, dim))
+            })?;
+        let initial_centroids: Vec<f64> =
+            rand::seq::index::sample(&mut StdRng::seed_from_u64(bench.seed), n_points, k)
+                .into_iter()
+                .flat_map(|i| data[i * dim..(i + 1) * dim].to_vec())
+                .collect();
+        let seq_fit = || {
+            let run = kmeans_nd_seq(&data, dim, max_iters, tolerance, initial_centroids.clone());
+            (run.iterations, None)
+        };
+        let par_fit = || {
+            let run = pool.install(|| {
+                kmeans_nd_par(&data, dim, max_iters, tolerance, initial_centroids.clone())
+            });
+            (run.iterations, None)
+        };
+        results.push(sample(threads, n_points, k, dim, bench, seq_fit, par_fit));
+    }
+    Ok(results)
+}
+
+impl ScalingResult {
+    /// Median sequential and parallel seconds per Lloyd iteration.
+    pub fn time_per_iteration(&self) -> (f64, f64) {
+        let per = |time: f64, iterations: &[usize]| {
+            time * iterations.len() as f64 / iterations.iter().sum::<usize>().max(1) as f64
+        };
+        (
+            per(self.seq.median, &self.seq_iterations),
+            per(self.par.median, &self.par_iterations),
+        )
+    }
+}
+
+fn join(values: &[impl ToString]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Writes one row per result. The first seven columns match the older
+/// result files; raw samples are `;`-separated and nothing is rounded.
+/// The `Seq*`/`Par*` phase columns are mean seconds per run; outliers are
+/// listed as indices into the raw samples.
+/// The file is plain CSV; the machine and build are recorded separately
+/// (see [`Environment`]).
+pub fn save_csv(filename: impl AsRef<Path>, results: &[ScalingResult]) -> Result<()> {
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(
+        file,
+        "Threads,MeanSeq,StdSeq,MeanPar,StdPar,Speedup,Efficiency,NPoints,K,MedianSeq,MedianPar,ItersSeq,ItersPar,TimesSeq,TimesPar,\
+         SeqInit,SeqAssign,SeqAccum,SeqUpdate,ParInit,ParAssign,ParAccum,ParUpdate,\
+         IqrSeq,IqrPar,CiLowSeq,CiHighSeq,CiLowPar,CiHighPar,OutliersSeq,OutliersPar,Dim,MedianSpeedup"
+    )?;
+    for r in results {
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            r.threads,
+            r.seq.mean,
+            r.seq.std,
+            r.par.mean,
+            r.par.std,
+            r.speedup,
+            r.efficiency,
+            r.n_points,
+            r.k,
+            r.seq.median,
+            r.par.median,
+            join(&r.seq_iterations),
+            join(&r.par_iterations),
+            join(&r.seq.times),
+            join(&r.par.times),
+            r.seq_phases.init,
+            r.seq_phases.assignment,
+            r.seq_phases.accumulation,
+            r.seq_phases.update,
+            r.par_phases.init,
+            r.par_phases.assignment,
+            r.par_phases.accumulation,
+            r.par_phases.update,
+            r.seq.iqr,
+            r.par.iqr,
+            r.seq.median_ci.0,
+            r.seq.median_ci.1,
+            r.par.median_ci.0,
+            r.par.median_ci.1,
+            join(&r.seq.outliers),
+            join(&r.par.outliers),
+            r.dim,
+            r.median_speedup.map_or(String::new(), |s| s.to_string()),
+        )?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Results as written by [`save_json`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultsFile {
+    pub environment: Environment,
+    pub results: Vec<ScalingResult>,
+}
+
+// JSON files before the environment block held just the array.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonResults {
+    WithEnvironment(Box<ResultsFile>),
+    Plain(Vec<ScalingResult>),
+}
+
+/// Writes the results with the [`Environment`] they were measured in.
+pub fn save_json(filename: impl AsRef<Path>, results: &[ScalingResult]) -> Result<()> {
+    let writer = BufWriter::new(File::create(filename)?);
+    let file = ResultsFile {
+        environment: Environment::capture(),
+        results: results.to_vec(),
+    };
+    serde_json::to_writer_pretty(writer, &file)?;
+    Ok(())
+}
+
+// A row of a results CSV. Only the first seven columns exist in the older
+// files, so everything after them is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+struct CsvRow {
+    threads: usize,
+    mean_seq: f64,
+    std_seq: f64,
+    mean_par: f64,
+    std_par: f64,
+    speedup: f64,
+    efficiency: f64,
+    n_points: Option<usize>,
+    k: Option<usize>,
+    median_seq: Option<f64>,
+    median_par: Option<f64>,
+    iters_seq: Option<String>,
+    iters_par: Option<String>,
+    times_seq: Option<String>,
+    times_par: Option<String>,
+    seq_init: Option<f64>,
+    seq_assign: Option<f64>,
+    seq_accum: Option<f64>,
+    seq_update: Option<f64>,
+    par_init: Option<f64>,
+    par_assign: Option<f64>,
+    par_accum: Option<f64>,
+    par_update: Option<f64>,
+    iqr_seq: Option<f64>,
+    iqr_par: Option<f64>,
+    ci_low_seq: Option<f64>,
+    ci_high_seq: Option<f64>,
+    ci_low_par: Option<f64>,
+    ci_high_par: Option<f64>,
+    outliers_seq: Option<String>,
+    outliers_par: Option<String>,
+    dim: Option<usize>,
+    median_speedup: Option<f64>,
+}
+
+fn split<T: std::str::FromStr>(field: Option<String>, line: u64) -> Result<Vec<T>> {
+    field
+        .as_deref()
+        .unwrap_or("")
+        .split(';')
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse().map_err(|_| Error::Parse {
+                line,
+                message: format!("invalid list entry {:?}", v),
+            })
+        })
+        .collect()
+}
+
+fn phases(init: Option<f64>, a: Option<f64>, b: Option<f64>, c: Option<f64>) -> PhaseTotals {
+    PhaseTotals {
+        init: init.unwrap_or(0.0),
+        assignment: a.unwrap_or(0.0),
+        accumulation: b.unwrap_or(0.0),
+        update: c.unwrap_or(0.0),
+    }
+}
+
+/// Reads results written by [`save_json`] or [`save_csv`]. CSV files from
+/// before the raw samples were recorded load with empty samples and NaN
+/// for the statistics they lack.
+pub fn load_results(filename: impl AsRef<Path>) -> Result<Vec<ScalingResult>> {
+    let path = filename.as_ref();
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => {
+            let file: JsonResults =
+                serde_json::from_reader(std::io::BufReader::new(File::open(path)?))?;
+            Ok(match file {
+                JsonResults::WithEnvironment(file) => file.results,
+                JsonResults::Plain(results) => results,
+            })
+        }
+        Some("csv") => {
+            // Some older files open with `#` environment lines.
+            let mut reader = csv::ReaderBuilder::new()
+                .comment(Some(b'#'))
+                .from_path(path)?;
+            let headers = reader.headers()?.clone();
+            let mut results = Vec::new();
+            for record in reader.records() {
+                let record = record?;
+                let line = record.position().map_or(0, |p| p.line());
+                let row: CsvRow = record.deserialize(Some(&headers))?;
+                let seq = TimingStats {
+                    times: split(row.times_seq, line)?,
+                    mean: row.mean_seq,
+                    std: row.std_seq,
+                    median: row.median_seq.unwrap_or(f64::NAN),
+                    iqr: row.iqr_seq.unwrap_or(f64::NAN),
+                    median_ci: (
+                        row.ci_low_seq.unwrap_or(f64::NAN),
+                        row.ci_high_seq.unwrap_or(f64::NAN),
+                    ),
+                    outliers: split(row.outliers_seq, line)?,
+                };
+                let par = TimingStats {
+                    times: split(row.times_par, line)?,
+                    mean: row.mean_par,
+                    std: row.std_par,
+                    median: row.median_par.unwrap_or(f64::NAN),
+                    iqr: row.iqr_par.unwrap_or(f64::NAN),
+                    median_ci: (
+                        row.ci_low_par.unwrap_or(f64::NAN),
+                        row.ci_high_par.unwrap_or(f64::NAN),
+                    ),
+                    outliers: split(row.outliers_par, line)?,
+                };
+                results.push(ScalingResult {
+                    threads: row.threads,
+                    n_points: row.n_points.unwrap_or(0),
+                    k: row.k.unwrap_or(0),
+                    dim: row.dim.unwrap_or(2),
+                    seq,
+                    par,
+                    speedup: row.speedup,
+                    efficiency: row.efficiency,
+                    median_speedup: row.median_speedup,
+                    seq_iterations: split(row.iters_seq, line)?,
+                    par_iterations: split(row.iters_par, line)?,
+                    seq_phases: phases(row.seq_init, row.seq_assign, row.seq_accum, row.seq_update),
+                    par_phases: phases(row.par_init, row.par_assign, row.par_accum, row.par_update),
+                });
+            }
+            Ok(results)
+        }
+        _ => Err(Error::InvalidFormat(format!(
+            "{} is neither .csv nor .json",
+            path.display()
+        ))),
+    }
+}
+
+/// Speedup predicted by Amdahl's (fixed size) or Gustafson's (scaled size)
+/// law for parallel fraction `p` on `cores` cores.
+pub fn law_speedup(law: &str, p: f64, cores: usize) -> f64 {
+    let c = cores as f64;
+    if law == "gustafson" {
+        c - (1.0 - p) * (c - 1.0)
+    } else {
+        1.0 / ((1.0 - p) + p / c)
+    }
+}
@@ -1,250 +1,226 @@
-mod kmeans_par;
-mod kmeans_seq;
-mod point;
+use clap::Parser;
+use kmeans::experiment_spec::ExperimentSpec;
+use kmeans::parallel_fraction::fit_parallel_fraction;
+use kmeans::regression::{compare, RegressionConfig};
+use kmeans::scaling::{
+    dim_scaling, k_scaling, load_results, outlier_warnings, save_csv, save_json, strong_scaling,
+    weak_scaling, ScalingResult,
+};
+use kmeans::scaling_plots::{plot_dim_scaling, plot_k_scaling, plot_scaling_series, Series};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-use plotters::prelude::*;
-use point::Point;
-use rand::prelude::*;
-use std::fs::File;
-use std::io::Write;
-use std::time::Instant;
-
-fn create_test_data(n_points: usize) -> Vec<Point> {
-    let mut rng = thread_rng();
-    (0..n_points)
-        .map(|_| Point {
-            x: rng.gen_range(0.0..100.0),
-            y: rng.gen_range(0.0..100.0),
-        })
-        .collect()
-}
-
-fn mean_std(times: &[f64]) -> (f64, f64) {
-    let mean = times.iter().sum::<f64>() / times.len() as f64;
-    let std = (times.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / times.len() as f64).sqrt();
-    (mean, std)
+/// Run the scaling experiments described by a spec file. Exits with status
+/// 2 when --baseline is given and a configuration got significantly slower.
+#[derive(Parser)]
+#[command(name = "experiments")]
+struct Cli {
+    /// TOML or JSON experiment spec; the standard strong and weak runs when
+    /// omitted
+    spec: Option<PathBuf>,
+    /// Directory with baseline `{kind}_scaling.json` (or `.csv`) results to
+    /// compare the parallel times against
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+    /// Relative slowdown of the parallel time allowed before a configuration
+    /// is flagged, e.g. 0.05 for 5%
+    #[arg(long, default_value_t = 0.05)]
+    max_slowdown: f64,
+    /// Also require a one-sided Mann-Whitney test on the raw samples to
+    /// reject at this significance level
+    #[arg(long)]
+    alpha: Option<f64>,
 }
 
-fn strong_scaling(
-    n_points: usize,
-    k: usize,
-    max_iters: usize,
-    tolerance: f64,
-    max_threads: usize,
-    n_runs: usize,
-) -> Vec<(usize, f64, f64, f64, f64, f64, f64)> {
-    let mut results = Vec::new();
-    for threads in 1..=max_threads {
-        let mut seq_times = Vec::new();
-        let mut par_times = Vec::new();
-        for _ in 0..n_runs {
-            let points = create_test_data(n_points);
-            let initial_centroids: Vec<Point> = points
-                .choose_multiple(&mut thread_rng(), k)
-                .cloned()
-                .collect();
-            let start_seq = Instant::now();
-            let _ = kmeans_seq::kmeans_seq(
-                &points,
-                k,
-                max_iters,
-                tolerance,
-                Some(initial_centroids.clone()),
-            );
-            let seq_time = start_seq.elapsed().as_secs_f64();
-            seq_times.push(seq_time);
+const KINDS: [&str; 4] = ["strong", "weak", "k", "dim"];
 
-            let start_par = Instant::now();
-            let pool = rayon::ThreadPoolBuilder::new()
-                .num_threads(threads)
-                .build()
-                .unwrap();
-            pool.install(|| {
-                let _ = kmeans_par::kmeans_par(
-                    &points,
-                    k,
-                    max_iters,
-                    tolerance,
-                    Some(initial_centroids.clone()),
-                );
-            });
-            let par_time = start_par.elapsed().as_secs_f64();
-            par_times.push(par_time);
+// Baseline results of one experiment kind, preferring JSON for its raw
+// samples.
+fn load_baseline(dir: &Path, kind: &str) -> kmeans::error::Result<Option<Vec<ScalingResult>>> {
+    for ext in ["json", "csv"] {
+        let path = dir.join(format!("{}_scaling.{}", kind, ext));
+        if path.exists() {
+            return load_results(&path).map(Some);
         }
-        let (mean_seq, std_seq) = mean_std(&seq_times);
-        let (mean_par, std_par) = mean_std(&par_times);
-        let speedup = mean_seq / mean_par;
-        let efficiency = speedup / threads as f64;
-        results.push((
-            threads, mean_seq, std_seq, mean_par, std_par, speedup, efficiency,
-        ));
     }
-    results
+    Ok(None)
 }
 
-fn weak_scaling(
-    base_points: usize,
-    k: usize,
-    max_iters: usize,
-    tolerance: f64,
-    max_threads: usize,
-    n_runs: usize,
-) -> Vec<(usize, f64, f64, f64, f64, f64, f64)> {
-    let mut results = Vec::new();
-    for threads in 1..=max_threads {
-        let n_points = base_points * threads;
-        let mut seq_times = Vec::new();
-        let mut par_times = Vec::new();
-        for _ in 0..n_runs {
-            let points = create_test_data(n_points);
-            let initial_centroids: Vec<Point> = points
-                .choose_multiple(&mut thread_rng(), k)
-                .cloned()
-                .collect();
-            let start_seq = Instant::now();
-            let _ = kmeans_seq::kmeans_seq(
-                &points,
-                k,
-                max_iters,
-                tolerance,
-                Some(initial_centroids.clone()),
-            );
-            let seq_time = start_seq.elapsed().as_secs_f64();
-            seq_times.push(seq_time);
-
-            let start_par = Instant::now();
-            let pool = rayon::ThreadPoolBuilder::new()
-                .num_threads(threads)
-                .build()
-                .unwrap();
-            pool.install(|| {
-                let _ = kmeans_par::kmeans_par(
-                    &points,
-                    k,
-                    max_iters,
-                    tolerance,
-                    Some(initial_centroids.clone()),
-                );
-            });
-            let par_time = start_par.elapsed().as_secs_f64();
-            par_times.push(par_time);
-        }
-        let (mean_seq, std_seq) = mean_std(&seq_times);
-        let (mean_par, std_par) = mean_std(&par_times);
-        let speedup = mean_seq / mean_par;
-        let efficiency = speedup / threads as f64;
-        results.push((
-            threads, mean_seq, std_seq, mean_par, std_par, speedup, efficiency,
-        ));
+// Writes the CSV, JSON and plot for one experiment and prints the parallel
+// fraction fitted to its speedups.
+fn report(
+    spec: &ExperimentSpec,
+    kind: &str,
+    law: &str,
+    results: &[ScalingResult],
+) -> kmeans::error::Result<()> {
+    for warning in outlier_warnings(results) {
+        eprintln!("warning: {}", warning);
+    }
+    let fit = fit_parallel_fraction(results, law);
+    save_csv(spec.output_path(&format!("{}_scaling.csv", kind)), results)?;
+    save_json(spec.output_path(&format!("{}_scaling.json", kind)), results)?;
+    if let Some(fit) = &fit {
+        print!("{}", fit);
+        let file = std::fs::File::create(spec.output_path(&format!("{}_scaling_fit.json", kind)))?;
+        serde_json::to_writer_pretty(file, fit)?;
     }
-    results
+    let series = [Series {
+        label: "Measured speedup".to_string(),
+        results,
+        fitted_p: fit.and_then(|f| f.valid_p()),
+    }];
+    for ext in ["png", "svg"] {
+        plot_scaling_series(
+            spec.output_path(&format!("{}_scaling.{}", kind, ext)),
+            &series,
+            law,
+            spec.parallel_fraction,
+            &spec.plot,
+        )?;
+    }
+    Ok(())
 }
 
-fn save_csv(filename: &str, results: &[(usize, f64, f64, f64, f64, f64, f64)]) {
-    let mut file = File::create(filename).unwrap();
-    writeln!(
-        file,
-        "Threads,MeanSeq,StdSeq,MeanPar,StdPar,Speedup,Efficiency"
-    )
-    .unwrap();
-    for r in results {
-        writeln!(
-            file,
-            "{},{:.4},{:.4},{:.4},{:.4},{:.2},{:.2}",
-            r.0, r.1, r.2, r.3, r.4, r.5, r.6
-        )
-        .unwrap();
+// Returns whether any configuration regressed against the baseline.
+fn run(cli: Cli) -> kmeans::error::Result<bool> {
+    let spec = match &cli.spec {
+        Some(path) => ExperimentSpec::load(path)?,
+        None => ExperimentSpec::standard(),
+    };
+    // Read the baselines first: they may live in the output directory.
+    let mut baselines = Vec::new();
+    if let Some(dir) = &cli.baseline {
+        for kind in KINDS {
+            if let Some(results) = load_baseline(dir, kind)? {
+                baselines.push((kind, results));
+            }
+        }
+        if baselines.is_empty() {
+            return Err(kmeans::error::Error::InvalidFormat(format!(
+                "no *_scaling.json or *_scaling.csv baseline in {}",
+                dir.display()
+            )));
+        }
     }
-}
+    spec.prepare_output(cli.spec.as_deref())?;
+    let mut fresh: Vec<(&str, Vec<ScalingResult>)> = Vec::new();
 
-fn plot_scaling(
-    filename: &str,
-    results: &[(usize, f64, f64, f64, f64, f64, f64)],
-    law: &str,
-    p: f64,
-) {
-    let root = BitMapBackend::new(filename, (800, 600)).into_drawing_area();
-    root.fill(&WHITE).unwrap();
-    let cores: Vec<usize> = results.iter().map(|r| r.0).collect();
-    let speedup: Vec<f64> = results.iter().map(|r| r.5).collect();
-    let max_cores = *cores.last().unwrap();
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Scaling experiment", ("sans-serif", 30))
-        .margin(40)
-        .x_label_area_size(40)
-        .y_label_area_size(60)
-        .build_cartesian_2d(1..max_cores, 0.0..(max_cores as f64 + 1.0))
-        .unwrap();
-    chart
-        .configure_mesh()
-        .x_desc("Number of cores")
-        .y_desc("Speedup")
-        .draw()
-        .unwrap();
-    chart
-        .draw_series(LineSeries::new(
-            cores.iter().zip(speedup.iter()).map(|(&x, &y)| (x, y)),
-            &BLUE,
-        ))
-        .unwrap()
-        .label("Measured speedup")
-        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 1, y)], &BLUE));
-    chart
-        .draw_series(LineSeries::new(
-            cores.iter().map(|&x| (x, x as f64)),
-            &BLACK,
-        ))
-        .unwrap()
-        .label("Ideal scaling")
-        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 1, y)], &BLACK));
-    if law == "amdahl" {
-        chart
-            .draw_series(LineSeries::new(
-                cores.iter().map(|&c| (c, 1.0 / ((1.0 - p) + p / c as f64))),
-                &RED,
-            ))
-            .unwrap()
-            .label(&format!("Amdahl's law p={}", p))
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 1, y)], &RED));
-    } else if law == "gustafson" {
-        chart
-            .draw_series(LineSeries::new(
-                cores
-                    .iter()
-                    .map(|&c| (c, c as f64 - (1.0 - p) * (c as f64 - 1.0))),
-                &RED,
-            ))
-            .unwrap()
-            .label(&format!("Gustafson's law p={}", p))
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 1, y)], &RED));
+    if let Some(strong) = &spec.strong {
+        println!("Running strong scaling experiment...");
+        let strong_results = strong_scaling(
+            &spec.dataset,
+            strong.n_points,
+            spec.k,
+            spec.max_iters,
+            spec.tolerance,
+            &spec.threads,
+            &spec.bench,
+        )?;
+        report(&spec, "strong", "amdahl", &strong_results)?;
+        fresh.push(("strong", strong_results));
+        println!("Strong scaling done.");
     }
-    chart
-        .configure_series_labels()
-        .background_style(&WHITE.mix(0.8))
-        .border_style(&BLACK)
-        .draw()
-        .unwrap();
-}
 
-fn main() {
-    let k = 4;
-    let max_iters = 100;
-    let tolerance = 1e-3;
-    let max_threads = 8;
-    let n_runs = 2;
-    let n_points = 100000;
-    let base_points = 200000;
-    let p = 0.9; // Parallel fraction
+    if let Some(weak) = &spec.weak {
+        println!("Running weak scaling experiment...");
+        let weak_results = weak_scaling(
+            &spec.dataset,
+            weak.base_points,
+            spec.k,
+            spec.max_iters,
+            spec.tolerance,
+            &spec.threads,
+            &spec.bench,
+        )?;
+        report(&spec, "weak", "gustafson", &weak_results)?;
+        fresh.push(("weak", weak_results));
+        println!("Weak scaling done.");
+    }
+    if let Some(sweep) = &spec.k_scaling {
+        println!("Running k scaling experiment...");
+        let k_results = k_scaling(
+            &spec.dataset,
+            sweep.n_points,
+            &sweep.ks,
+            spec.max_iters,
+            spec.tolerance,
+            sweep.threads,
+            &spec.bench,
+        )?;
+        for warning in outlier_warnings(&k_results) {
+            eprintln!("warning: {}", warning);
+        }
+        save_csv(spec.output_path("k_scaling.csv"), &k_results)?;
+        save_json(spec.output_path("k_scaling.json"), &k_results)?;
+        plot_k_scaling(spec.output_path("k_scaling.png"), &k_results)?;
+        plot_k_scaling(spec.output_path("k_scaling.svg"), &k_results)?;
+        fresh.push(("k", k_results));
+        println!("K scaling done.");
+    }
+    if let Some(sweep) = &spec.dim_scaling {
+        println!("Running dimension scaling experiment...");
+        let dim_results = dim_scaling(
+            &spec.dataset,
+            sweep.n_points,
+            spec.k,
+            &sweep.dims,
+            spec.max_iters,
+            spec.tolerance,
+            sweep.threads,
+            &spec.bench,
+        )?;
+        for warning in outlier_warnings(&dim_results) {
+            eprintln!("warning: {}", warning);
+        }
+        save_csv(spec.output_path("dim_scaling.csv"), &dim_results)?;
+        save_json(spec.output_path("dim_scaling.json"), &dim_results)?;
+        plot_dim_scaling(spec.output_path("dim_scaling.png"), &dim_results)?;
+        plot_dim_scaling(spec.output_path("dim_scaling.svg"), &dim_results)?;
+        fresh.push(("dim", dim_results));
+        println!("Dimension scaling done.");
+    }
+    println!("Results written to {}", spec.output_dir.display());
 
-    println!("Running strong scaling experiment...");
-    let strong_results = strong_scaling(n_points, k, max_iters, tolerance, max_threads, n_runs);
-    save_csv("strong_scaling_rust13.csv", &strong_results);
-    plot_scaling("strong_scaling_rust13.png", &strong_results, "amdahl", p);
-    println!("Strong scaling done.");
+    let config = RegressionConfig {
+        max_slowdown: cli.max_slowdown,
+        alpha: cli.alpha,
+    };
+    let mut regressed = false;
+    let mut compared = 0;
+    for (kind, baseline) in &baselines {
+        let Some((_, results)) = fresh.iter().find(|(k, _)| k == kind) else {
+            eprintln!(
+                "warning: the baseline has {} scaling results but this spec does not run that experiment",
+                kind
+            );
+            continue;
+        };
+        let comparison = compare(baseline, results, &config);
+        println!("\n{} scaling vs baseline:", kind);
+        print!("{}", comparison);
+        compared += comparison.comparisons.len();
+        regressed |= comparison.regressed();
+    }
+    if let (Some(dir), 0) = (&cli.baseline, compared) {
+        return Err(kmeans::error::Error::InvalidFormat(format!(
+            "no configuration of this run matches the baseline in {}, so nothing was compared",
+            dir.display()
+        )));
+    }
+    if regressed {
+        eprintln!("error: parallel time regressed against the baseline");
+    }
+    Ok(regressed)
+}
 
-    println!("Running weak scaling experiment...");
-    let weak_results = weak_scaling(base_points, k, max_iters, tolerance, max_threads, n_runs);
-    save_csv("weak_scaling_rust6.csv", &weak_results);
-    plot_scaling("weak_scaling_rust6.png", &weak_results, "gustafson", p);
-    println!("Weak scaling done.");
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(false) => ExitCode::SUCCESS,
+        Ok(true) => ExitCode::from(2),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,506 @@
+use crate::error::{Error, Result};
+use crate::scaling::{law_speedup, ScalingResult};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// What the error bars around each measured point show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorBars {
+    /// Mean speedup ± one standard deviation, propagated from the times.
+    #[default]
+    Std,
+    /// Median speedup with the interval from the bootstrap 95% intervals
+    /// of the median times.
+    Ci,
+    None,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlotOptions {
+    pub error_bars: ErrorBars,
+    /// Base-2 logarithmic thread and speedup axes.
+    pub log2: bool,
+}
+
+/// One set of results drawn on a shared chart.
+pub struct Series<'a> {
+    pub label: String,
+    pub results: &'a [ScalingResult],
+    /// Parallel fraction fitted to these results, drawn as a faded law
+    /// curve when present.
+    pub fitted_p: Option<f64>,
+}
+
+fn law_name(law: &str) -> Option<&'static str> {
+    match law {
+        "amdahl" => Some("Amdahl's law"),
+        "gustafson" => Some("Gustafson's law"),
+        _ => None,
+    }
+}
+
+// The speedup drawn for one measurement: the median ratio with CI bars,
+// which bound the medians, and the mean ratio otherwise.
+fn plotted_speedup(r: &ScalingResult, bars: ErrorBars) -> f64 {
+    match (bars, r.median_speedup) {
+        (ErrorBars::Ci, Some(median)) => median,
+        _ => r.speedup,
+    }
+}
+
+// Lower and upper speedup bounds of one measurement, if it has the
+// statistics the chosen error bars need.
+fn speedup_bounds(r: &ScalingResult, bars: ErrorBars) -> Option<(f64, f64)> {
+    let (lo, hi) = match bars {
+        ErrorBars::Std => {
+            let rel = ((r.seq.std / r.seq.mean).powi(2) + (r.par.std / r.par.mean).powi(2)).sqrt();
+            (r.speedup * (1.0 - rel), r.speedup * (1.0 + rel))
+        }
+        ErrorBars::Ci => (
+            r.seq.median_ci.0 / r.par.median_ci.1,
+            r.seq.median_ci.1 / r.par.median_ci.0,
+        ),
+        ErrorBars::None => return None,
+    };
+    (lo.is_finite() && hi.is_finite()).then_some((lo.max(f64::MIN_POSITIVE), hi))
+}
+
+/// Plots the measured speedup with the ideal line and the `law` curve for
+/// the assumed `p`, plus the curve for `fitted_p` when given. The format
+/// follows the extension: `.svg` or a bitmap.
+pub fn plot_scaling(
+    filename: impl AsRef<Path>,
+    results: &[ScalingResult],
+    law: &str,
+    p: f64,
+    fitted_p: Option<f64>,
+) -> Result<()> {
+    let series = [Series {
+        label: "Measured speedup".to_string(),
+        results,
+        fitted_p,
+    }];
+    plot_scaling_series(filename, &series, law, p, &PlotOptions::default())
+}
+
+/// Overlays several result sets: speedup with error bars on top and
+/// parallel efficiency below.
+pub fn plot_scaling_series(
+    filename: impl AsRef<Path>,
+    series: &[Series],
+    law: &str,
+    p: f64,
+    opts: &PlotOptions,
+) -> Result<()> {
+    let path = filename.as_ref();
+    if path.extension().is_some_and(|e| e == "svg") {
+        let root = SVGBackend::new(path, (800, 900)).into_drawing_area();
+        draw_scaling(&root, series, law, p, opts)?;
+        root.present()?;
+    } else {
+        let root = BitMapBackend::new(path, (800, 900)).into_drawing_area();
+        draw_scaling(&root, series, law, p, opts)?;
+        root.present()?;
+    }
+    Ok(())
+}
+
+fn draw_scaling<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    series: &[Series],
+    law: &str,
+    p: f64,
+    opts: &PlotOptions,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let (upper, lower) = root.split_vertically(550);
+
+    let all = || series.iter().flat_map(|s| s.results.iter());
+    let min_cores = all()
+        .map(|r| r.threads)
+        .min()
+        .ok_or_else(|| Error::InvalidFormat("no scaling results to plot".to_string()))?;
+    let max_cores = all()
+        .map(|r| r.threads)
+        .max()
+        .unwrap_or(1)
+        .max(min_cores + 1);
+    let mut cores: Vec<usize> = all().map(|r| r.threads).collect();
+    cores.sort_unstable();
+    cores.dedup();
+
+    // Log axes are linear axes over log2 values with 2^v tick labels.
+    let log2 = opts.log2;
+    let tx = move |c: usize| if log2 { (c as f64).log2() } else { c as f64 };
+    let ty = move |s: f64| if log2 { s.log2() } else { s };
+    // One tick per power of two on log axes.
+    let x_ticks = if log2 {
+        (tx(max_cores) - tx(min_cores)).ceil() as usize + 1
+    } else {
+        10
+    };
+    let label = move |v: &f64| {
+        if log2 {
+            format!("{}", (2f64.powf(*v) * 100.0).round() / 100.0)
+        } else {
+            format!("{}", v)
+        }
+    };
+
+    let upper_bounds = all()
+        .flat_map(|r| speedup_bounds(r, opts.error_bars).map(|b| b.1))
+        .chain(all().map(|r| plotted_speedup(r, opts.error_bars)))
+        .fold(max_cores as f64, f64::max);
+    let lower_bounds = all()
+        .flat_map(|r| speedup_bounds(r, opts.error_bars).map(|b| b.0))
+        .chain(all().map(|r| plotted_speedup(r, opts.error_bars)))
+        .fold(1.0, f64::min);
+    let y_range = if log2 {
+        ty(lower_bounds * 0.8)..ty(upper_bounds * 1.25)
+    } else {
+        0.0..upper_bounds + 1.0
+    };
+
+    let mut chart = ChartBuilder::on(&upper)
+        .caption("Scaling experiment", ("sans-serif", 30))
+        .margin(40)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(tx(min_cores)..tx(max_cores), y_range)?;
+    chart
+        .configure_mesh()
+        .x_desc("Number of cores")
+        .y_desc("Speedup")
+        .x_labels(x_ticks)
+        .x_label_formatter(&label)
+        .y_label_formatter(&label)
+        .draw()?;
+    chart
+        .draw_series(LineSeries::new(
+            cores.iter().map(|&c| (tx(c), ty(c as f64))),
+            &BLACK,
+        ))?
+        .label("Ideal scaling")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLACK));
+    if let Some(name) = law_name(law) {
+        chart
+            .draw_series(LineSeries::new(
+                cores.iter().map(|&c| (tx(c), ty(law_speedup(law, p, c)))),
+                &RED,
+            ))?
+            .label(format!("{} p={}", name, p))
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+    }
+    for (i, s) in series.iter().enumerate() {
+        let color = series_color(i, series.len());
+        chart
+            .draw_series(LineSeries::new(
+                s.results
+                    .iter()
+                    .map(|r| (tx(r.threads), ty(plotted_speedup(r, opts.error_bars)))),
+                color.stroke_width(2),
+            ))?
+            .label(s.label.clone())
+            .legend(move |(x, y)| Rectangle::new([(x, y - 2), (x + 20, y + 2)], color.filled()));
+        chart.draw_series(s.results.iter().filter_map(|r| {
+            speedup_bounds(r, opts.error_bars).map(|(lo, hi)| {
+                let mid = plotted_speedup(r, opts.error_bars);
+                ErrorBar::new_vertical(tx(r.threads), ty(lo), ty(mid), ty(hi), color, 8)
+            })
+        }))?;
+        if let (Some(name), Some(fitted)) = (law_name(law), s.fitted_p) {
+            // Green next to a single blue series, as the plots always used.
+            let faded: ShapeStyle = if series.len() == 1 {
+                GREEN.into()
+            } else {
+                color.mix(0.5).into()
+            };
+            chart
+                .draw_series(LineSeries::new(
+                    cores
+                        .iter()
+                        .map(|&c| (tx(c), ty(law_speedup(law, fitted, c)))),
+                    faded,
+                ))?
+                .label(format!("{} fitted p={:.3}", name, fitted))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], faded));
+        }
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    let max_efficiency = all()
+        .flat_map(|r| {
+            let hi = speedup_bounds(r, opts.error_bars).map_or(r.speedup, |b| b.1);
+            [r.efficiency, hi / r.threads as f64]
+        })
+        .fold(1.0, f64::max);
+    let mut chart = ChartBuilder::on(&lower)
+        .margin(40)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(tx(min_cores)..tx(max_cores), 0.0..max_efficiency * 1.1)?;
+    chart
+        .configure_mesh()
+        .x_desc("Number of cores")
+        .y_desc("Efficiency")
+        .x_labels(x_ticks)
+        .x_label_formatter(&label)
+        .draw()?;
+    chart.draw_series(LineSeries::new(
+        [(tx(min_cores), 1.0), (tx(max_cores), 1.0)],
+        &BLACK,
+    ))?;
+    for (i, s) in series.iter().enumerate() {
+        let color = series_color(i, series.len());
+        chart.draw_series(LineSeries::new(
+            s.results.iter().map(|r| (tx(r.threads), r.efficiency)),
+            color.stroke_width(2),
+        ))?;
+        chart.draw_series(s.results.iter().filter_map(|r| {
+            let n = r.threads as f64;
+            speedup_bounds(r, opts.error_bars).map(|(lo, hi)| {
+                ErrorBar::new_vertical(tx(r.threads), lo / n, r.efficiency, hi / n, color, 8)
+            })
+        }))?;
+    }
+    Ok(())
+}
+
+/// Plots mean wall-clock times against thread count for several result
+/// sets on a shared log time axis: parallel runs solid with std error bars,
+/// sequential runs faded.
+pub fn plot_times(filename: impl AsRef<Path>, series: &[Series]) -> Result<()> {
+    let path = filename.as_ref();
+    if path.extension().is_some_and(|e| e == "svg") {
+        let root = SVGBackend::new(path, (800, 600)).into_drawing_area();
+        draw_times(&root, series)?;
+        root.present()?;
+    } else {
+        let root = BitMapBackend::new(path, (800, 600)).into_drawing_area();
+        draw_times(&root, series)?;
+        root.present()?;
+    }
+    Ok(())
+}
+
+fn draw_times<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, series: &[Series]) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let all = || series.iter().flat_map(|s| s.results.iter());
+    let min_cores = all()
+        .map(|r| r.threads)
+        .min()
+        .ok_or_else(|| Error::InvalidFormat("no scaling results to plot".to_string()))?;
+    let max_cores = all()
+        .map(|r| r.threads)
+        .max()
+        .unwrap_or(1)
+        .max(min_cores + 1);
+    let times = || {
+        all().flat_map(|r| {
+            [
+                r.seq.mean,
+                r.par.mean,
+                r.par.mean - r.par.std,
+                r.par.mean + r.par.std,
+            ]
+        })
+    };
+    let t_min = times().filter(|&t| t > 0.0).fold(f64::INFINITY, f64::min);
+    let t_max = times().fold(0.0, f64::max);
+
+    let mut chart = ChartBuilder::on(root)
+        .caption("Execution time", ("sans-serif", 30))
+        .margin(40)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d(
+            min_cores..max_cores,
+            (t_min * 0.8..t_max * 1.25).log_scale(),
+        )?;
+    chart
+        .configure_mesh()
+        .x_desc("Number of cores")
+        .y_desc("Seconds")
+        .y_label_formatter(&|t| format!("{:.1e}", t))
+        .draw()?;
+    for (i, s) in series.iter().enumerate() {
+        let color = series_color(i, series.len());
+        chart
+            .draw_series(LineSeries::new(
+                s.results.iter().map(|r| (r.threads, r.par.mean)),
+                color.stroke_width(2),
+            ))?
+            .label(format!("{} parallel", s.label))
+            .legend(move |(x, y)| Rectangle::new([(x, y - 2), (x + 20, y + 2)], color.filled()));
+        chart.draw_series(s.results.iter().filter(|r| r.par.std > 0.0).map(|r| {
+            ErrorBar::new_vertical(
+                r.threads,
+                (r.par.mean - r.par.std).max(t_min * 0.8),
+                r.par.mean,
+                r.par.mean + r.par.std,
+                color,
+                8,
+            )
+        }))?;
+        let faded = color.mix(0.5);
+        chart
+            .draw_series(LineSeries::new(
+                s.results.iter().map(|r| (r.threads, r.seq.mean)),
+                faded,
+            ))?
+            .label(format!("{} sequential", s.label))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], faded));
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+    Ok(())
+}
+
+// Blue for a single series, as the plots always used; a palette otherwise.
+fn series_color(i: usize, count: usize) -> RGBColor {
+    if count == 1 {
+        BLUE
+    } else {
+        let (r, g, b) = Palette99::pick(i).rgb();
+        RGBColor(r, g, b)
+    }
+}
+
+/// Plots seconds per iteration (top) and speedup (bottom) against k on
+/// log2 axes, for results of [`crate::scaling::k_scaling`].
+pub fn plot_k_scaling(filename: impl AsRef<Path>, results: &[ScalingResult]) -> Result<()> {
+    plot_sweep(filename.as_ref(), results, "k", |r| r.k)
+}
+
+/// [`plot_k_scaling`] against the number of coordinates per point, for
+/// results of [`crate::scaling::dim_scaling`].
+pub fn plot_dim_scaling(filename: impl AsRef<Path>, results: &[ScalingResult]) -> Result<()> {
+    plot_sweep(filename.as_ref(), results, "Dimension", |r| r.dim)
+}
+
+fn plot_sweep(
+    path: &Path,
+    results: &[ScalingResult],
+    axis: &str,
+    x: fn(&ScalingResult) -> usize,
+) -> Result<()> {
+    if path.extension().is_some_and(|e| e == "svg") {
+        let root = SVGBackend::new(path, (800, 900)).into_drawing_area();
+        draw_sweep(&root, results, axis, x)?;
+        root.present()?;
+    } else {
+        let root = BitMapBackend::new(path, (800, 900)).into_drawing_area();
+        draw_sweep(&root, results, axis, x)?;
+        root.present()?;
+    }
+    Ok(())
+}
+
+fn draw_sweep<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    results: &[ScalingResult],
+    axis: &str,
+    x: fn(&ScalingResult) -> usize,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    if results.is_empty() {
+        return Err(Error::InvalidFormat(
+            "no scaling results to plot".to_string(),
+        ));
+    }
+    root.fill(&WHITE)?;
+    let (upper, lower) = root.split_vertically(550);
+
+    let xs: Vec<f64> = results.iter().map(|r| x(r) as f64).collect();
+    let per_iter: Vec<(f64, f64)> = results.iter().map(|r| r.time_per_iteration()).collect();
+    let x_min = xs.iter().cloned().fold(f64::INFINITY, f64::min);
+    let x_max = xs.iter().cloned().fold(0.0, f64::max).max(x_min * 2.0);
+    let t_min = per_iter
+        .iter()
+        .flat_map(|&(s, p)| [s, p])
+        .fold(f64::INFINITY, f64::min);
+    let t_max = per_iter
+        .iter()
+        .flat_map(|&(s, p)| [s, p])
+        .fold(0.0, f64::max);
+
+    let mut chart = ChartBuilder::on(&upper)
+        .caption(
+            format!("Time per iteration, {} threads", results[0].threads),
+            ("sans-serif", 30),
+        )
+        .margin(40)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d(
+            (x_min..x_max).log_scale().base(2.0),
+            (t_min * 0.8..t_max * 1.25).log_scale(),
+        )?;
+    chart
+        .configure_mesh()
+        .x_desc(axis)
+        .y_desc("Seconds per iteration")
+        .x_label_formatter(&|x| format!("{:.0}", x))
+        .y_label_formatter(&|t| format!("{:.1e}", t))
+        .draw()?;
+    chart
+        .draw_series(LineSeries::new(
+            xs.iter().zip(per_iter.iter()).map(|(&x, &(s, _))| (x, s)),
+            &RED,
+        ))?
+        .label("Sequential")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+    chart
+        .draw_series(LineSeries::new(
+            xs.iter().zip(per_iter.iter()).map(|(&x, &(_, p))| (x, p)),
+            &BLUE,
+        ))?
+        .label("Parallel")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE));
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    let max_speedup = results.iter().map(|r| r.speedup).fold(1.0, f64::max);
+    let mut chart = ChartBuilder::on(&lower)
+        .margin(40)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d((x_min..x_max).log_scale().base(2.0), 0.0..max_speedup * 1.1)?;
+    chart
+        .configure_mesh()
+        .x_desc(axis)
+        .y_desc("Speedup")
+        .x_label_formatter(&|x| format!("{:.0}", x))
+        .draw()?;
+    chart.draw_series(LineSeries::new(
+        results.iter().map(|r| (x(r) as f64, r.speedup)),
+        &BLUE,
+    ))?;
+    // Below this line the parallel version is slower than the sequential one.
+    chart.draw_series(LineSeries::new([(x_min, 1.0), (x_max, 1.0)], &BLACK))?;
+    Ok(())
+}
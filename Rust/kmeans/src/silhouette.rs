@@ -0,0 +1,152 @@
+use crate::point::{euclidean_distance, Point};
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::SeedableRng;
+use rayon::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct Silhouette {
+    pub mean: f64,
+    pub per_point: Vec<f64>,
+    pub per_cluster: Vec<f64>,
+}
+
+fn cluster_sizes(assignments: &[usize]) -> Vec<usize> {
+    let k = assignments.iter().max().map_or(0, |&m| m + 1);
+    let mut counts = vec![0usize; k];
+    for &cluster in assignments {
+        counts[cluster] += 1;
+    }
+    counts
+}
+
+fn coefficient(a: f64, b: f64) -> f64 {
+    let max_ab = a.max(b);
+    if max_ab > 0.0 {
+        (b - a) / max_ab
+    } else {
+        0.0
+    }
+}
+
+fn point_silhouette(
+    point: &Point,
+    own: usize,
+    points: &[Point],
+    assignments: &[usize],
+    counts: &[usize],
+) -> f64 {
+    if counts[own] < 2 {
+        return 0.0;
+    }
+    let mut sums = vec![0.0; counts.len()];
+    for (other, &cluster) in points.iter().zip(assignments.iter()) {
+        sums[cluster] += euclidean_distance(point, other);
+    }
+    let a = sums[own] / (counts[own] - 1) as f64;
+    let b = (0..counts.len())
+        .filter(|&j| j != own && counts[j] > 0)
+        .map(|j| sums[j] / counts[j] as f64)
+        .fold(f64::MAX, f64::min);
+    coefficient(a, b)
+}
+
+fn summarize(per_point: Vec<f64>, labels: impl Iterator<Item = usize>, k: usize) -> Silhouette {
+    let mut sums = vec![0.0; k];
+    let mut counts = vec![0usize; k];
+    for (&s, cluster) in per_point.iter().zip(labels) {
+        sums[cluster] += s;
+        counts[cluster] += 1;
+    }
+    let per_cluster = sums
+        .iter()
+        .zip(counts.iter())
+        .map(|(&s, &c)| if c > 0 { s / c as f64 } else { 0.0 })
+        .collect();
+    let mean = if per_point.is_empty() {
+        0.0
+    } else {
+        per_point.iter().sum::<f64>() / per_point.len() as f64
+    };
+    Silhouette {
+        mean,
+        per_point,
+        per_cluster,
+    }
+}
+
+/// Exact silhouette coefficient of every point. Every point is compared
+/// against every other point, so this is O(n^2) and meant for moderate n.
+pub fn silhouette(points: &[Point], assignments: &[usize]) -> Silhouette {
+    let counts = cluster_sizes(assignments);
+    let per_point = if counts.iter().filter(|&&c| c > 0).count() < 2 {
+        vec![0.0; points.len()]
+    } else {
+        points
+            .par_iter()
+            .zip(assignments.par_iter())
+            .map(|(point, &own)| point_silhouette(point, own, points, assignments, &counts))
+            .collect()
+    };
+    summarize(per_point, assignments.iter().copied(), counts.len())
+}
+
+pub fn silhouette_score(points: &[Point], assignments: &[usize]) -> f64 {
+    silhouette(points, assignments).mean
+}
+
+/// Silhouette of `sample_size` randomly chosen points, each still measured
+/// against the full data set (O(sample_size * n)). `per_point[i]` belongs to
+/// the point at the i-th returned index.
+pub fn sampled_silhouette(
+    points: &[Point],
+    assignments: &[usize],
+    sample_size: usize,
+    seed: u64,
+) -> (Silhouette, Vec<usize>) {
+    let counts = cluster_sizes(assignments);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut indices =
+        index::sample(&mut rng, points.len(), sample_size.min(points.len())).into_vec();
+    indices.sort_unstable();
+
+    let per_point = if counts.iter().filter(|&&c| c > 0).count() < 2 {
+        vec![0.0; indices.len()]
+    } else {
+        indices
+            .par_iter()
+            .map(|&i| point_silhouette(&points[i], assignments[i], points, assignments, &counts))
+            .collect()
+    };
+    let labels = indices.iter().map(|&i| assignments[i]);
+    (summarize(per_point, labels, counts.len()), indices)
+}
+
+/// Simplified silhouette: `a` is the distance to the point's own centroid and
+/// `b` the distance to the nearest other centroid. O(n * k), so it scales to
+/// millions of points.
+pub fn simplified_silhouette(
+    points: &[Point],
+    centroids: &[Point],
+    assignments: &[usize],
+) -> Silhouette {
+    let per_point = if centroids.len() < 2 {
+        vec![0.0; points.len()]
+    } else {
+        points
+            .par_iter()
+            .zip(assignments.par_iter())
+            .map(|(point, &own)| {
+                let a = euclidean_distance(point, &centroids[own]);
+                let b = centroids
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != own)
+                    .map(|(_, c)| euclidean_distance(point, c))
+                    .fold(f64::MAX, f64::min);
+                coefficient(a, b)
+            })
+            .collect()
+    };
+    summarize(per_point, assignments.iter().copied(), centroids.len())
+}
@@ -0,0 +1,151 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Critical values of Student's t distribution for a two-sided 95%
+/// interval, indexed by degrees of freedom 1..=30.
+const T_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+pub fn mean_std(times: &[f64]) -> (f64, f64) {
+    let mean = times.iter().sum::<f64>() / times.len() as f64;
+    let std = (times.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / times.len() as f64).sqrt();
+    (mean, std)
+}
+
+pub fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Two-sided 95% critical value of Student's t with `df` degrees of freedom;
+/// the normal value is used above 30.
+pub fn t_critical_95(df: usize) -> f64 {
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => T_95[df - 1],
+        _ => 1.96,
+    }
+}
+
+/// Linearly interpolated `q`-quantile (`0 <= q <= 1`) of `values`.
+pub fn quantile(values: &[f64], q: f64) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let pos = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+/// Percentile bootstrap 95% confidence interval for the median, from
+/// `resamples` resamples drawn with a seeded generator.
+pub fn bootstrap_median_ci(values: &[f64], resamples: usize, seed: u64) -> (f64, f64) {
+    if values.is_empty() || resamples == 0 {
+        return (f64::NAN, f64::NAN);
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut resample = vec![0.0; values.len()];
+    let medians: Vec<f64> = (0..resamples)
+        .map(|_| {
+            for v in resample.iter_mut() {
+                *v = values[rng.gen_range(0..values.len())];
+            }
+            median(&resample)
+        })
+        .collect();
+    (quantile(&medians, 0.025), quantile(&medians, 0.975))
+}
+
+/// Indices of values outside Tukey's fences `[Q1 - 1.5 IQR, Q3 + 1.5 IQR]`.
+pub fn tukey_outliers(values: &[f64]) -> Vec<usize> {
+    let q1 = quantile(values, 0.25);
+    let q3 = quantile(values, 0.75);
+    let fence = 1.5 * (q3 - q1);
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, &v)| v < q1 - fence || v > q3 + fence)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// Standard normal CDF, via the Abramowitz and Stegun 7.1.26 approximation
+// of erf (absolute error below 1.5e-7).
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// One-sided Mann–Whitney U test of whether `a` tends to be larger than
+/// `b`; returns the p-value. Ties count half. Small samples use the exact
+/// null distribution of U, larger ones the normal approximation.
+pub fn mann_whitney_greater(a: &[f64], b: &[f64]) -> f64 {
+    let (m, n) = (a.len(), b.len());
+    if m == 0 || n == 0 {
+        return f64::NAN;
+    }
+    let u: f64 = a
+        .iter()
+        .flat_map(|x| {
+            b.iter().map(move |y| match x.partial_cmp(y) {
+                Some(std::cmp::Ordering::Greater) => 1.0,
+                Some(std::cmp::Ordering::Equal) => 0.5,
+                _ => 0.0,
+            })
+        })
+        .sum();
+
+    if m * n <= 400 {
+        // counts[u] = orderings of m + n values giving statistic u, built
+        // up one sample size at a time.
+        let max_u = m * n;
+        let mut table = vec![vec![vec![0f64; max_u + 1]; n + 1]; m + 1];
+        for (i, row) in table.iter_mut().enumerate() {
+            for (j, counts) in row.iter_mut().enumerate() {
+                if i == 0 || j == 0 {
+                    counts[0] = 1.0;
+                }
+            }
+        }
+        for i in 1..=m {
+            for j in 1..=n {
+                for k in 0..=i * j {
+                    let with_a_largest = if k >= j { table[i - 1][j][k - j] } else { 0.0 };
+                    table[i][j][k] = with_a_largest + table[i][j - 1][k];
+                }
+            }
+        }
+        let counts = &table[m][n];
+        let total: f64 = counts.iter().sum();
+        let threshold = u.ceil() as usize;
+        counts[threshold.min(max_u)..].iter().sum::<f64>() / total
+    } else {
+        let mean = (m * n) as f64 / 2.0;
+        let sd = ((m * n * (m + n + 1)) as f64 / 12.0).sqrt();
+        1.0 - normal_cdf((u - 0.5 - mean) / sd)
+    }
+}
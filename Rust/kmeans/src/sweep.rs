@@ -0,0 +1,69 @@
+use crate::n_init::kmeans_n_init;
+use crate::point::Point;
+use crate::silhouette::silhouette_score;
+use rayon::prelude::*;
+use std::ops::RangeInclusive;
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+pub struct SweepResult {
+    pub k: usize,
+    pub inertia: f64,
+    pub silhouette: f64,
+    pub time: f64,
+}
+
+/// Fits every k in `ks` concurrently (one rayon task per k), keeping the
+/// best of `n_init` seeded restarts so the inertia curve is reproducible
+/// and close to monotone.
+pub fn k_sweep(
+    points: &[Point],
+    ks: RangeInclusive<usize>,
+    max_iters: usize,
+    tolerance: f64,
+    n_init: usize,
+    seed: u64,
+) -> Vec<SweepResult> {
+    let ks: Vec<usize> = ks.collect();
+    ks.into_par_iter()
+        .map(|k| {
+            let start = Instant::now();
+            let fit = kmeans_n_init(points, k, max_iters, tolerance, n_init, seed);
+            let time = start.elapsed().as_secs_f64();
+            SweepResult {
+                k,
+                inertia: fit.inertia,
+                silhouette: silhouette_score(points, &fit.assignments),
+                time,
+            }
+        })
+        .collect()
+}
+
+/// Elbow of a decreasing, convex inertia curve using the Kneedle method:
+/// both axes are scaled to [0, 1] and the knee is the k where the curve lies
+/// furthest below the straight line joining its end points.
+pub fn find_elbow(results: &[SweepResult]) -> Option<usize> {
+    if results.len() < 3 {
+        return None;
+    }
+    let k_min = results.first()?.k as f64;
+    let k_max = results.last()?.k as f64;
+    let (i_min, i_max) = results.iter().fold((f64::MAX, f64::MIN), |(lo, hi), r| {
+        (lo.min(r.inertia), hi.max(r.inertia))
+    });
+    if k_max <= k_min || i_max <= i_min {
+        return None;
+    }
+
+    let mut best: Option<(usize, f64)> = None;
+    for r in results {
+        let x = (r.k as f64 - k_min) / (k_max - k_min);
+        let y = (r.inertia - i_min) / (i_max - i_min);
+        let diff = (1.0 - y) - x;
+        if best.is_none_or(|(_, d)| diff > d) {
+            best = Some((r.k, diff));
+        }
+    }
+    best.filter(|&(_, d)| d > 0.0).map(|(k, _)| k)
+}